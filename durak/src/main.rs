@@ -13,9 +13,10 @@ use durak_lib::{
 };
 
 use network::NetworkPlugin;
-use persistence::Configuration;
+use persistence::{is_valid_server_address, Configuration, DEFAULT_SERVER_ADDRESS};
 use round::RoundPlugin;
-use ui::UiPlugin;
+use session::SessionPlugin;
+use ui::{notification::Notifications, UiPlugin};
 
 fn main() {
     App::new()
@@ -38,13 +39,30 @@ fn main() {
         .add_plugins(UiPlugin)
         .add_plugins(RoundPlugin)
         .add_plugins(NetworkPlugin)
+        .add_plugins(SessionPlugin)
         .add_systems(Startup, startup)
         .run();
 }
 
-fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn startup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut notifications: ResMut<Notifications>,
+) {
     match Configuration::load() {
-        Ok(configuration) => {
+        Ok(mut configuration) => {
+            if !is_valid_server_address(&configuration.server_address) {
+                warn!(
+                    "configuration contains invalid server address `{}`",
+                    configuration.server_address
+                );
+                notifications.push(format!(
+                    "Invalid server address `{}`, using `{DEFAULT_SERVER_ADDRESS}` instead",
+                    configuration.server_address
+                ));
+                configuration.server_address = String::from(DEFAULT_SERVER_ADDRESS);
+                let _ = configuration.save();
+            }
             commands.insert_resource(configuration);
         }
         Err(_) => {
@@ -91,4 +109,6 @@ pub struct GameStarted {
 pub struct GameEnded {
     pub winner_id: PlayerId,
     pub winner_name: String,
+    /// Name of the player that ended the game by leaving it.
+    pub forfeited_name: Option<String>,
 }
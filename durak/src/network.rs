@@ -12,8 +12,11 @@ use bevy_mod_reqwest::{
     *,
 };
 
+use durak_lib::errors::ErrorResponse;
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::persistence::Configuration;
+
 /// Plugin that manages all network functionality of the game.
 pub struct NetworkPlugin;
 
@@ -21,20 +24,32 @@ impl Plugin for NetworkPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app.add_plugins(ReqwestPlugin)
             .init_resource::<ReqwestClient>()
+            .add_plugins(RequestPlugin::<VersionRequest>::new())
+            .add_plugins(RequestPlugin::<LobbiesRequest>::new())
             .add_plugins(RequestPlugin::<CreateGameRequest>::new())
             .add_plugins(RequestPlugin::<JoinGameRequest>::new())
+            .add_plugins(RequestPlugin::<SpectateRequest>::new())
+            .add_plugins(RequestPlugin::<SpectatorStatusRequest>::new())
+            .add_plugins(RequestPlugin::<EnterQueueRequest>::new())
+            .add_plugins(RequestPlugin::<QueueStatusRequest>::new())
+            .add_plugins(RequestPlugin::<LeaveQueueRequest>::new())
             .add_plugins(RequestPlugin::<LeaveGameRequest>::new())
             .add_plugins(RequestPlugin::<StatusRequest>::new())
+            .add_plugins(RequestPlugin::<RejoinCheckRequest>::new())
+            .add_plugins(RequestPlugin::<ReadyRequest>::new())
             .add_plugins(RequestPlugin::<StartGameRequest>::new())
+            .add_plugins(RequestPlugin::<KickRequest>::new())
+            .add_plugins(RequestPlugin::<SwapSeatsRequest>::new())
             .add_plugins(RequestPlugin::<PlayCardRequest>::new())
             .add_plugins(RequestPlugin::<TakeRequest>::new())
-            .add_plugins(RequestPlugin::<RetreatRequest>::new());
+            .add_plugins(RequestPlugin::<RetreatRequest>::new())
+            .add_plugins(RequestPlugin::<PostMessageRequest>::new());
     }
 }
 
 /// Generic plugin that handles requests of specific type.
 ///
-/// It adds systems that handle requests and [OnResponse] event.
+/// It adds systems that handle requests, [OnResponse] and [OnError] events.
 struct RequestPlugin<R: MyRequest>(PhantomData<R>);
 
 impl<R: MyRequest> RequestPlugin<R> {
@@ -52,23 +67,28 @@ where
             FixedUpdate,
             (send_request::<R>, handle_responses::<R>).chain(),
         )
-        .add_event::<OnResponse<R>>();
+        .add_event::<OnResponse<R>>()
+        .add_event::<OnError<R>>();
     }
 }
 
 fn send_request<R: MyRequest + Component>(
     client: Res<ReqwestClient>,
     mut commands: Commands,
+    config: Res<Configuration>,
     requests: Query<(Entity, &R), Added<R>>,
 ) {
     for (entity, request) in requests.iter() {
         let mut builder = client
             .0
-            .request(request.method(), request.url())
+            .request(request.method(), request.url(&config.server_address))
             .headers(request.headers());
         if let Some(ref query) = request.query() {
             builder = builder.query(query);
         }
+        if let Some(ref body) = request.body() {
+            builder = builder.json(body);
+        }
         let request = builder
             .build()
             .expect("request should be built successfully");
@@ -80,39 +100,51 @@ fn handle_responses<R: MyRequest + Component>(
     mut commands: Commands,
     results: Query<(Entity, &ReqwestBytesResult), With<R>>,
     mut event_writer: EventWriter<OnResponse<R>>,
+    mut error_writer: EventWriter<OnError<R>>,
 ) {
-    // TODO: notify on error
     for (entity, res) in results.iter() {
+        commands.entity(entity).despawn_recursive();
         let Some(str) = res.as_str() else {
+            warn!("request to the server failed");
             continue;
         };
-        let Ok(response) = serde_json::from_str(str) else {
+        // Every unsuccessful response carries an error envelope.
+        if let Ok(error) = serde_json::from_str::<ErrorResponse>(str) {
+            debug!("server responded with error: {}", error.message);
+            error_writer.send(OnError::new(error));
             continue;
-        };
-        event_writer.send(OnResponse(response));
-        commands.entity(entity).despawn_recursive();
+        }
+        match serde_json::from_str(str) {
+            Ok(response) => event_writer.send(OnResponse(response)),
+            Err(err) => warn!("failed to parse server response: {err}"),
+        }
     }
 }
 
 /// Convenience trait that is used to map various custom requests into reqwest's request.
 pub trait MyRequest {
-    // TODO: make base url changeable.
-    const URL: &'static str = "http://127.0.0.1:3000";
-
     /// Type that will be returned by the server.
     type Response: Debug + DeserializeOwned + Send + Sync;
 
     /// Type that will be serialized into query params.
     type Query: Serialize;
 
+    /// Type that will be serialized into json body.
+    type Body: Serialize;
+
     fn method(&self) -> Method;
 
-    fn url(&self) -> Url;
+    /// Returns url of the endpoint on the server with provided address.
+    fn url(&self, server: &str) -> Url;
 
     fn query(&self) -> Option<Self::Query> {
         None
     }
 
+    fn body(&self) -> Option<Self::Body> {
+        None
+    }
+
     fn headers(&self) -> HeaderMap {
         HeaderMap::new()
     }
@@ -121,3 +153,19 @@ pub trait MyRequest {
 /// Event that is being fired when response for `R` has arrived.
 #[derive(Debug, Event)]
 pub struct OnResponse<R: MyRequest>(pub R::Response);
+
+/// Event that is being fired when server rejected request `R`.
+#[derive(Debug, Event)]
+pub struct OnError<R: MyRequest> {
+    pub error: ErrorResponse,
+    marker: PhantomData<R>,
+}
+
+impl<R: MyRequest> OnError<R> {
+    fn new(error: ErrorResponse) -> Self {
+        Self {
+            error,
+            marker: PhantomData,
+        }
+    }
+}
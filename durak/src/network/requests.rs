@@ -4,35 +4,88 @@ use bevy_mod_reqwest::reqwest::{
     Method, Url,
 };
 use durak_lib::{
+    chat::{ChatMessage, PostMessageData},
     game::card::Card,
-    network::{AuthHeader, CreateGameData, CreateGameResponse, JoinGameData, JoinGameResponse},
-    status::StatusResponse,
+    identifiers::PlayerId,
+    matchmaking::{QueueData, QueueStatus, Ticket, TicketQuery},
+    network::{
+        AuthHeader, CreateGameData, CreateGameResponse, JoinGameData, JoinGameResponse, KickQuery,
+        LobbySummary, SpectateData, SpectateResponse, SwapSeatsQuery, VersionResponse,
+    },
+    status::{SpectatorStatus, StatusResponse},
 };
 
+use crate::persistence::server_url;
+
 use super::MyRequest;
 
+/// Returns url of the endpoint at provided server.
+///
+/// Server address is validated when configuration is loaded or changed.
+fn endpoint(server: &str, path: &str) -> Url {
+    server_url(server)
+        .and_then(|url| url.join(path).ok())
+        .expect("server address should be valid")
+}
+
+#[derive(Debug, Component)]
+pub struct VersionRequest;
+
+impl MyRequest for VersionRequest {
+    type Response = VersionResponse;
+
+    type Query = ();
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/version")
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct LobbiesRequest;
+
+impl MyRequest for LobbiesRequest {
+    type Response = Vec<LobbySummary>;
+
+    type Query = ();
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/lobbies")
+    }
+}
+
 #[derive(Debug, Component)]
 pub struct CreateGameRequest(pub CreateGameData);
 
 impl MyRequest for CreateGameRequest {
     type Response = CreateGameResponse;
 
-    type Query = [(&'static str, String); 2];
+    type Query = ();
+
+    type Body = CreateGameData;
 
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/create", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/create")
     }
 
-    fn query(&self) -> Option<Self::Query> {
-        Some([
-            ("name", self.0.name.clone()),
-            ("password", self.0.password.clone()),
-        ])
+    fn body(&self) -> Option<Self::Body> {
+        Some(self.0.clone())
     }
 }
 
@@ -42,23 +95,141 @@ pub struct JoinGameRequest(pub JoinGameData);
 impl MyRequest for JoinGameRequest {
     type Response = JoinGameResponse;
 
-    type Query = [(&'static str, String); 3];
+    type Query = ();
+
+    type Body = JoinGameData;
 
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/join", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/join")
+    }
+
+    fn body(&self) -> Option<Self::Body> {
+        Some(self.0.clone())
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct SpectateRequest(pub SpectateData);
+
+impl MyRequest for SpectateRequest {
+    type Response = SpectateResponse;
+
+    type Query = ();
+
+    type Body = SpectateData;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/spectate")
+    }
+
+    fn body(&self) -> Option<Self::Body> {
+        Some(self.0.clone())
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct SpectatorStatusRequest(pub AuthHeader);
+
+impl MyRequest for SpectatorStatusRequest {
+    type Response = SpectatorStatus;
+
+    type Query = ();
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/spectate/status")
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.0.into_header());
+        map
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct EnterQueueRequest(pub QueueData);
+
+impl MyRequest for EnterQueueRequest {
+    type Response = QueueStatus;
+
+    type Query = ();
+
+    type Body = QueueData;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/queue")
+    }
+
+    fn body(&self) -> Option<Self::Body> {
+        Some(self.0.clone())
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct QueueStatusRequest(pub Ticket);
+
+impl MyRequest for QueueStatusRequest {
+    type Response = QueueStatus;
+
+    type Query = TicketQuery;
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/queue/status")
+    }
+
+    fn query(&self) -> Option<Self::Query> {
+        Some(TicketQuery {
+            ticket: self.0.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct LeaveQueueRequest(pub Ticket);
+
+impl MyRequest for LeaveQueueRequest {
+    type Response = ();
+
+    type Query = TicketQuery;
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/queue/leave")
     }
 
     fn query(&self) -> Option<Self::Query> {
-        Some([
-            ("id", self.0.id.0.to_string()),
-            ("name", self.0.name.clone()),
-            ("password", self.0.password.clone()),
-        ])
+        Some(TicketQuery {
+            ticket: self.0.clone(),
+        })
     }
 }
 
@@ -70,13 +241,14 @@ impl MyRequest for LeaveGameRequest {
 
     type Query = ();
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/leave", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/leave")
     }
 
     fn headers(&self) -> HeaderMap {
@@ -94,13 +266,65 @@ impl MyRequest for StatusRequest {
 
     type Query = ();
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::GET
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/status", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/status")
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.0.into_header());
+        map
+    }
+}
+
+/// Checks whether the game of the saved session is still live.
+#[derive(Debug, Component)]
+pub struct RejoinCheckRequest(pub AuthHeader);
+
+impl MyRequest for RejoinCheckRequest {
+    type Response = StatusResponse;
+
+    type Query = ();
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/status")
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.0.into_header());
+        map
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct ReadyRequest(pub AuthHeader);
+
+impl MyRequest for ReadyRequest {
+    type Response = bool;
+
+    type Query = ();
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/ready")
     }
 
     fn headers(&self) -> HeaderMap {
@@ -118,13 +342,14 @@ impl MyRequest for StartGameRequest {
 
     type Query = ();
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/start", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/start")
     }
 
     fn headers(&self) -> HeaderMap {
@@ -134,6 +359,76 @@ impl MyRequest for StartGameRequest {
     }
 }
 
+#[derive(Debug, Component)]
+pub struct KickRequest {
+    pub auth: AuthHeader,
+    pub player: PlayerId,
+}
+
+impl MyRequest for KickRequest {
+    type Response = ();
+
+    type Query = KickQuery;
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/kick")
+    }
+
+    fn query(&self) -> Option<Self::Query> {
+        Some(KickQuery {
+            player: self.player,
+        })
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.auth.into_header());
+        map
+    }
+}
+
+#[derive(Debug, Component)]
+pub struct SwapSeatsRequest {
+    pub auth: AuthHeader,
+    pub first: u8,
+    pub second: u8,
+}
+
+impl MyRequest for SwapSeatsRequest {
+    type Response = ();
+
+    type Query = SwapSeatsQuery;
+
+    type Body = ();
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/seats/swap")
+    }
+
+    fn query(&self) -> Option<Self::Query> {
+        Some(SwapSeatsQuery {
+            first: self.first,
+            second: self.second,
+        })
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.auth.into_header());
+        map
+    }
+}
+
 #[derive(Debug, Component)]
 pub struct PlayCardRequest {
     pub auth: AuthHeader,
@@ -145,13 +440,14 @@ impl MyRequest for PlayCardRequest {
 
     type Query = Card;
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/play", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/play")
     }
 
     fn query(&self) -> Option<Self::Query> {
@@ -173,13 +469,14 @@ impl MyRequest for TakeRequest {
 
     type Query = ();
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/take", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/take")
     }
 
     fn headers(&self) -> HeaderMap {
@@ -197,13 +494,14 @@ impl MyRequest for RetreatRequest {
 
     type Query = ();
 
+    type Body = ();
+
     fn method(&self) -> Method {
         Method::POST
     }
 
-    fn url(&self) -> Url {
-        let url = format!("{}/game/retreat", Self::URL);
-        Url::parse(&url).unwrap()
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/retreat")
     }
 
     fn headers(&self) -> HeaderMap {
@@ -212,3 +510,35 @@ impl MyRequest for RetreatRequest {
         map
     }
 }
+
+#[derive(Debug, Component)]
+pub struct PostMessageRequest {
+    pub auth: AuthHeader,
+    pub data: PostMessageData,
+}
+
+impl MyRequest for PostMessageRequest {
+    type Response = ChatMessage;
+
+    type Query = ();
+
+    type Body = PostMessageData;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn url(&self, server: &str) -> Url {
+        endpoint(server, "/game/chat")
+    }
+
+    fn body(&self) -> Option<Self::Body> {
+        Some(self.data.clone())
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        map.insert(AUTHORIZATION, self.auth.into_header());
+        map
+    }
+}
@@ -1,6 +1,11 @@
 mod default_name;
 
 use bevy::prelude::*;
+use bevy_mod_reqwest::reqwest::Url;
+use durak_lib::{
+    identifiers::{GameId, PlayerId},
+    network::Token,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -8,6 +13,9 @@ use std::path::PathBuf;
 
 use self::default_name::generate_default_name;
 
+/// Address of the server used unless configured otherwise.
+pub const DEFAULT_SERVER_ADDRESS: &str = env!("DURAK_SERVER_ADDRESS");
+
 /// Configuration file.
 #[derive(Debug, Resource, Serialize, Deserialize)]
 pub struct Configuration {
@@ -32,22 +40,85 @@ impl Configuration {
 
     /// Returns path to the configuration file.
     pub fn path() -> Result<PathBuf, ConfigurationError> {
-        let mut path = std::env::current_exe().map_err(|_| ConfigurationError)?;
-        path.pop();
-        path.push(PathBuf::from("data.toml"));
-        Ok(path)
+        data_path("data.toml")
     }
 }
 
+/// Returns `true` if requests can be sent to provided server address.
+pub fn is_valid_server_address(server: &str) -> bool {
+    server_url(server).is_some()
+}
+
+/// Returns url of the server root.
+///
+/// Address may start with `http://` or `https://`, plain `http` is used otherwise.
+pub fn server_url(server: &str) -> Option<Url> {
+    let url = if server.contains("://") {
+        Url::parse(server)
+    } else {
+        Url::parse(&format!("http://{server}"))
+    };
+    url.ok().filter(|url| {
+        matches!(url.scheme(), "http" | "https")
+            && url.has_host()
+            && url.path() == "/"
+            && url.query().is_none()
+    })
+}
+
 impl Default for Configuration {
     fn default() -> Self {
         Self {
             name: generate_default_name(),
-            server_address: String::from(env!("DURAK_SERVER_ADDRESS")),
+            server_address: String::from(DEFAULT_SERVER_ADDRESS),
         }
     }
 }
 
+/// Session of the game the player takes part in, so it survives restart of the client.
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+pub struct SavedSession {
+    /// Address of the server that hosts the game.
+    pub server: String,
+    pub game: GameId,
+    pub player: PlayerId,
+    pub token: Token,
+    pub name: String,
+}
+
+impl SavedSession {
+    /// Loads session from the disk.
+    pub fn load() -> Result<Self, ConfigurationError> {
+        let value = std::fs::read_to_string(Self::path()?).map_err(|_| ConfigurationError)?;
+        toml::from_str(&value).map_err(|_| ConfigurationError)
+    }
+
+    /// Saves session to the disk, replacing previous one.
+    pub fn save(&self) -> Result<(), ConfigurationError> {
+        let value = toml::to_string_pretty(self).map_err(|_| ConfigurationError)?;
+        std::fs::write(Self::path()?, value).map_err(|_| ConfigurationError)?;
+        Ok(())
+    }
+
+    /// Removes saved session from the disk.
+    pub fn remove() -> Result<(), ConfigurationError> {
+        std::fs::remove_file(Self::path()?).map_err(|_| ConfigurationError)
+    }
+
+    /// Returns path to the session file.
+    pub fn path() -> Result<PathBuf, ConfigurationError> {
+        data_path("session.toml")
+    }
+}
+
+/// Returns path to the file stored next to the executable.
+fn data_path(name: &str) -> Result<PathBuf, ConfigurationError> {
+    let mut path = std::env::current_exe().map_err(|_| ConfigurationError)?;
+    path.pop();
+    path.push(PathBuf::from(name));
+    Ok(path)
+}
+
 #[derive(Debug, Error)]
 #[error("failed to update configuration")]
 pub struct ConfigurationError;
@@ -7,14 +7,19 @@ use std::{collections::HashSet, time::Duration};
 use bevy::{prelude::*, time::common_conditions::on_timer};
 use durak_lib::{
     game::{card::CardSuit, hand::Hand, player::Opponent, table::Table},
-    status::{round::RoundStatus, StatusResponse},
+    identifiers::PlayerId,
+    status::{SpectatorStatus, StatusResponse},
 };
 
 use crate::{
-    network::{OnResponse, StatusRequest},
+    network::{OnError, OnResponse, SpectatorStatusRequest, StatusRequest},
     round::turn::Turn,
-    session::Session,
-    ui::game::display_ui,
+    session::{Session, SpectatorSession},
+    ui::{
+        chat::Chat,
+        game::{display_spectator_ui, display_timer, display_ui},
+        notification::Notifications,
+    },
     GameEnded, GameScreen,
 };
 
@@ -29,12 +34,24 @@ impl Plugin for RoundPlugin {
             .add_systems(OnEnter(GameScreen::Round), setup)
             .add_systems(
                 Update,
-                ((
-                    request_status.run_if(on_timer(Duration::from_secs_f32(0.25))),
-                    on_status_response,
-                    display_ui,
+                (
+                    (
+                        request_status.run_if(on_timer(Duration::from_secs_f32(0.25))),
+                        on_status_response,
+                        on_status_error,
+                        display_ui,
+                    )
+                        .run_if(resource_exists::<Session>()),
+                    (
+                        request_spectator_status.run_if(on_timer(Duration::from_secs_f32(0.25))),
+                        on_spectator_status_response,
+                        on_spectator_status_error,
+                        display_spectator_ui,
+                    )
+                        .run_if(resource_exists::<SpectatorSession>()),
+                    display_timer.run_if(resource_exists::<TurnTimer>()),
                 )
-                    .run_if(in_state(GameScreen::Round)),),
+                    .run_if(in_state(GameScreen::Round)),
             )
             .add_systems(OnExit(GameScreen::Round), cleanup);
     }
@@ -66,6 +83,7 @@ fn on_status_response(
     opponents: Query<(Entity, &mut Opponent)>,
     mut response: EventReader<OnResponse<StatusRequest>>,
     mut game_ended: EventWriter<GameEnded>,
+    mut chat: ResMut<Chat>,
 ) {
     let Some(OnResponse(status)) = response.iter().last() else {
         return;
@@ -73,6 +91,8 @@ fn on_status_response(
     match status {
         StatusResponse::Lobby(_) => todo!(),
         StatusResponse::Round(round) => {
+            chat.update(&round.chat);
+
             let mut hand = hand.single_mut();
             *hand = round.hand.clone();
 
@@ -92,48 +112,129 @@ fn on_status_response(
                 commands.remove_resource::<Turn>();
             }
 
-            update_opponent_list(commands, opponents, round);
+            match round.turn_time_left {
+                Some(seconds_left) => commands.insert_resource(TurnTimer {
+                    player: round.turn,
+                    seconds_left,
+                }),
+                None => commands.remove_resource::<TurnTimer>(),
+            }
+
+            update_opponent_list(commands, opponents, &round.opponents);
         }
         StatusResponse::Finished(status) => game_ended.send(GameEnded {
             winner_id: status.winner,
             winner_name: status.winner_name().to_string(),
+            forfeited_name: status.forfeited_name().map(str::to_string),
         }),
-        StatusResponse::Error(_) => todo!(),
     }
+}
 
-    fn update_opponent_list(
-        mut commands: Commands,
-        mut opponents: Query<(Entity, &mut Opponent)>,
-        round: &RoundStatus,
-    ) {
-        let mut processed = HashSet::with_capacity(6);
-        for (entity, mut opponent) in opponents.iter_mut() {
-            // Update existing
-            for received in round.opponents.iter() {
-                if opponent.id == received.id {
-                    if opponent.cards_number != received.cards_number {
-                        opponent.cards_number = received.cards_number;
-                    }
-                    if opponent.name != received.name {
-                        opponent.name = received.name.clone();
-                    }
-                    processed.insert(opponent.id);
+/// Synchronizes opponent entities with received list.
+fn update_opponent_list(
+    mut commands: Commands,
+    mut opponents: Query<(Entity, &mut Opponent)>,
+    received_opponents: &[Opponent],
+) {
+    let mut processed = HashSet::with_capacity(6);
+    for (entity, mut opponent) in opponents.iter_mut() {
+        // Update existing
+        for received in received_opponents.iter() {
+            if opponent.id == received.id {
+                if opponent.cards_number != received.cards_number {
+                    opponent.cards_number = received.cards_number;
                 }
+                if opponent.name != received.name {
+                    opponent.name = received.name.clone();
+                }
+                processed.insert(opponent.id);
             }
-            // Remove if not found
-            if !processed.contains(&opponent.id) {
-                commands.entity(entity).despawn_recursive();
-            }
         }
-        // Add new
-        for received in round.opponents.iter() {
-            if !processed.contains(&received.id) {
-                commands.spawn(received.clone());
+        // Remove if not found
+        if !processed.contains(&opponent.id) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    // Add new
+    for received in received_opponents.iter() {
+        if !processed.contains(&received.id) {
+            commands.spawn(received.clone());
+        }
+    }
+}
+
+/// Returns to main menu if the game is no longer accessible.
+fn on_status_error(
+    mut errors: EventReader<OnError<StatusRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut state: ResMut<NextState<GameScreen>>,
+) {
+    let Some(OnError { error, .. }) = errors.iter().last() else {
+        return;
+    };
+    notifications.push(format!("Lost access to the game: {}", error.message));
+    state.0 = Some(GameScreen::MainMenu);
+}
+
+fn request_spectator_status(session: Res<SpectatorSession>, mut commands: Commands) {
+    commands.spawn(SpectatorStatusRequest(session.into_header()));
+}
+
+/// Updates the table for spectator, whose hand always stays empty.
+fn on_spectator_status_response(
+    mut commands: Commands,
+    mut table: Query<&mut Table>,
+    mut deck: Query<&mut Deck>,
+    opponents: Query<(Entity, &mut Opponent)>,
+    mut response: EventReader<OnResponse<SpectatorStatusRequest>>,
+    mut game_ended: EventWriter<GameEnded>,
+) {
+    let Some(OnResponse(status)) = response.iter().last() else {
+        return;
+    };
+    match status {
+        SpectatorStatus::Lobby(_) => {}
+        SpectatorStatus::Round(round) => {
+            let mut table = table.single_mut();
+            *table = round.table.clone();
+
+            let mut deck = deck.single_mut();
+            if deck.left != round.deck_size {
+                deck.left = round.deck_size;
+            }
+
+            commands.insert_resource(Discard(round.discard.len()));
+            match round.turn_time_left {
+                Some(seconds_left) => commands.insert_resource(TurnTimer {
+                    player: round.turn,
+                    seconds_left,
+                }),
+                None => commands.remove_resource::<TurnTimer>(),
             }
+            update_opponent_list(commands, opponents, &round.players);
         }
+        SpectatorStatus::Finished(status) => game_ended.send(GameEnded {
+            winner_id: status.winner,
+            winner_name: status.winner_name().to_string(),
+            forfeited_name: status.forfeited_name().map(str::to_string),
+        }),
     }
 }
 
+fn on_spectator_status_error(
+    mut commands: Commands,
+    mut errors: EventReader<OnError<SpectatorStatusRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut state: ResMut<NextState<GameScreen>>,
+) {
+    let Some(OnError { error, .. }) = errors.iter().last() else {
+        return;
+    };
+    notifications.push(format!("Lost access to the game: {}", error.message));
+    commands.remove_resource::<SpectatorSession>();
+    state.0 = Some(GameScreen::MainMenu);
+}
+
 /// Despawns everything connected to round.
 fn cleanup(
     mut commands: Commands,
@@ -154,6 +255,20 @@ fn cleanup(
     for opponent in opponents.iter() {
         commands.entity(opponent).despawn_recursive();
     }
+    commands.remove_resource::<Discard>();
+    commands.remove_resource::<TurnTimer>();
+}
+
+/// Number of cards that were beaten off, known to spectators.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Discard(pub usize);
+
+/// Time left for the player on turn, known only if turns are limited.
+#[derive(Resource, Debug, Clone)]
+pub struct TurnTimer {
+    /// Player on turn.
+    pub player: PlayerId,
+    pub seconds_left: u64,
 }
 
 /// Trump suit for a round.
@@ -16,7 +16,10 @@ impl Plugin for CardInteractionPlugin {
             .add_event::<CardHoverEnded>()
             .add_systems(
                 Update,
-                (cursor_system, card_click)
+                (
+                    cursor_system,
+                    card_click.run_if(resource_exists::<Session>()),
+                )
                     .chain()
                     .run_if(in_state(GameScreen::Round)),
             );
@@ -67,7 +67,9 @@ fn spawn_trump_card(
     texture_atlas: Res<CardTextureAtlas>,
     mut events: EventReader<GameStarted>,
 ) {
-    let In(Some(deck)) = deck else { return; };
+    let In(Some(deck)) = deck else {
+        return;
+    };
     if let Some(GameStarted { trump, .. }) = events.iter().next() {
         let trump = commands
             .spawn((
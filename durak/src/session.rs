@@ -1,24 +1,160 @@
 use bevy::prelude::*;
 use durak_lib::{
+    errors::{ApiError, ErrorResponse},
     identifiers::{GameId, PlayerId},
+    invite::InviteLink,
     network::{AuthHeader, Token},
+    status::StatusResponse,
 };
 
+use crate::{
+    network::{LeaveGameRequest, OnError, OnResponse, RejoinCheckRequest, StatusRequest},
+    persistence::{Configuration, SavedSession},
+    GameEnded,
+};
+
+/// Keeps [Session] saved on the disk, so the game may be rejoined after restart of the client.
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                check_saved_session.run_if(resource_added::<Configuration>()),
+                on_rejoin_check_response,
+                on_rejoin_check_error,
+                save_session.run_if(resource_exists_and_changed::<Session>()),
+                forget_session,
+            ),
+        );
+    }
+}
+
 #[derive(Resource, Debug)]
 pub struct Session {
     pub name: String,
     pub id: PlayerId,
     pub game: GameId,
     pub token: Token,
-    pub is_host: bool,
+    /// Password of the game, used to invite other players.
+    ///
+    /// Unknown if session was restored after restart of the client.
+    pub password: Option<String>,
 }
 
 impl Session {
     pub fn into_header(&self) -> AuthHeader {
-        AuthHeader {
-            game_id: self.game,
-            player_id: self.id,
-            token: self.token,
+        AuthHeader(self.token.clone())
+    }
+
+    /// Restores session saved before restart of the client.
+    ///
+    /// Password of the game isn't saved, so it is unknown.
+    pub fn restore(saved: &SavedSession) -> Self {
+        Self {
+            name: saved.name.clone(),
+            id: saved.player,
+            game: saved.game,
+            token: saved.token.clone(),
+            password: None,
         }
     }
+
+    /// Returns link that invites other players into this game, if password of the game is known.
+    pub fn invite_link(&self, server: &str) -> Option<InviteLink> {
+        Some(InviteLink {
+            id: self.game,
+            password: self.password.clone()?,
+            server: Some(server.to_string()),
+        })
+    }
+}
+
+/// Session of the spectator, who only watches the game.
+#[derive(Resource, Debug)]
+pub struct SpectatorSession {
+    pub game: GameId,
+    pub token: Token,
+}
+
+impl SpectatorSession {
+    pub fn into_header(&self) -> AuthHeader {
+        AuthHeader(self.token.clone())
+    }
+}
+
+/// Asks the server whether the game of the saved session is still live.
+fn check_saved_session(mut commands: Commands, config: Res<Configuration>) {
+    let Ok(saved) = SavedSession::load() else {
+        return;
+    };
+    // Requests are sent to the configured server only.
+    if saved.server == config.server_address {
+        commands.spawn(RejoinCheckRequest(AuthHeader(saved.token)));
+    }
+}
+
+/// Offers to rejoin the game if it is still live.
+fn on_rejoin_check_response(
+    mut commands: Commands,
+    mut responses: EventReader<OnResponse<RejoinCheckRequest>>,
+) {
+    let Some(OnResponse(status)) = responses.iter().last() else {
+        return;
+    };
+    match status {
+        StatusResponse::Lobby(_) | StatusResponse::Round(_) => {
+            if let Ok(saved) = SavedSession::load() {
+                commands.insert_resource(saved);
+            }
+        }
+        StatusResponse::Finished(_) => {
+            let _ = SavedSession::remove();
+        }
+    }
+}
+
+/// Forgets the saved session once the server no longer accepts it.
+fn on_rejoin_check_error(mut errors: EventReader<OnError<RejoinCheckRequest>>) {
+    if errors.iter().any(|OnError { error, .. }| is_lost(error)) {
+        let _ = SavedSession::remove();
+    }
+}
+
+fn save_session(mut commands: Commands, session: Res<Session>, config: Res<Configuration>) {
+    // The previous game shouldn't be offered for rejoining anymore.
+    commands.remove_resource::<SavedSession>();
+    let saved = SavedSession {
+        server: config.server_address.clone(),
+        game: session.game,
+        player: session.id,
+        token: session.token.clone(),
+        name: session.name.clone(),
+    };
+    let _ = saved.save();
+}
+
+/// Removes saved session when the game is over or can't be accessed anymore.
+fn forget_session(
+    mut ended: EventReader<GameEnded>,
+    mut left: EventReader<OnResponse<LeaveGameRequest>>,
+    mut lost: EventReader<OnError<StatusRequest>>,
+    session: Option<Res<Session>>,
+) {
+    let ended = ended.iter().count() > 0 && session.is_some();
+    let lost = lost.iter().any(|OnError { error, .. }| is_lost(error));
+    if ended || left.iter().count() > 0 || lost {
+        let _ = SavedSession::remove();
+    }
+}
+
+/// Returns `true` if the error means that the game can't be rejoined anymore.
+///
+/// Other errors, such as rate limiting, may go away on their own.
+fn is_lost(error: &ErrorResponse) -> bool {
+    matches!(
+        error.error,
+        ApiError::AuthFailed(_) | ApiError::GameNotFound(_)
+    )
 }
@@ -1,21 +1,26 @@
+pub mod chat;
 pub mod game;
 mod game_end;
 mod main_menu;
+pub mod notification;
 mod utils;
 
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::{
-    egui::{CentralPanel, Frame, Margin, Ui},
+    egui::{CentralPanel, Context, Frame, Margin, Ui},
     EguiContexts, EguiPlugin,
 };
 
-use self::{game_end::show_game_end_ui, main_menu::MainMenuPlugin};
+use self::{
+    chat::ChatPlugin, game_end::show_game_end_ui, main_menu::MainMenuPlugin,
+    notification::NotificationPlugin,
+};
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((EguiPlugin, MainMenuPlugin))
+        app.add_plugins((EguiPlugin, ChatPlugin, MainMenuPlugin, NotificationPlugin))
             .add_systems(Update, show_game_end_ui);
     }
 }
@@ -32,6 +37,11 @@ impl UiContext<'_, '_> {
         self
     }
 
+    /// Returns egui context, so panels may be added before the central one.
+    pub fn ctx_mut(&mut self) -> &mut Context {
+        self.contexts.ctx_mut()
+    }
+
     pub fn show(&mut self, show: impl FnOnce(&mut Ui)) {
         let ctx = self.contexts.ctx_mut();
         CentralPanel::default()
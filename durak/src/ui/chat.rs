@@ -0,0 +1,128 @@
+//! Collapsible chat panel shown in the lobby and during the round.
+
+use bevy::prelude::*;
+use bevy_egui::egui::{self, Button, Key, ScrollArea, SidePanel, TextEdit};
+use durak_lib::chat::{ChatMessage, PostMessageData};
+
+use crate::{
+    network::{OnResponse, PostMessageRequest},
+    session::Session,
+};
+
+pub struct ChatPlugin;
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Chat>()
+            .add_systems(Update, on_post_response);
+    }
+}
+
+/// Chat of the current game.
+#[derive(Resource, Debug, Default)]
+pub struct Chat {
+    /// Whether the panel is expanded.
+    open: bool,
+    /// Message that is being typed.
+    draft: String,
+    messages: Vec<ChatMessage>,
+    /// Id of the latest message seen with the panel expanded.
+    seen: Option<u32>,
+}
+
+impl Chat {
+    /// Maximum length of the message accepted by the server.
+    const MAX_LENGTH: usize = 200;
+    const WIDTH: f32 = 350.;
+
+    /// Replaces messages with ones received in the status.
+    pub fn update(&mut self, messages: &[ChatMessage]) {
+        if self.messages != messages {
+            self.messages = messages.to_vec();
+        }
+    }
+
+    /// Forgets messages of the previous game.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn unread(&self) -> usize {
+        self.messages
+            .iter()
+            .filter(|message| self.seen.map_or(true, |seen| message.id > seen))
+            .count()
+    }
+
+    /// Shows the panel at the right side of the screen.
+    ///
+    /// Must be called before the central panel is shown.
+    pub fn show(&mut self, ctx: &egui::Context, commands: &mut Commands, session: &Session) {
+        if !self.open {
+            SidePanel::right("chat").resizable(false).show(ctx, |ui| {
+                let label = match self.unread() {
+                    0 => String::from("Chat"),
+                    unread => format!("Chat ({unread})"),
+                };
+                if ui.button(label).clicked() {
+                    self.open = true;
+                }
+            });
+            return;
+        }
+
+        self.seen = self.messages.last().map(|message| message.id).or(self.seen);
+        SidePanel::right("chat")
+            .resizable(false)
+            .exact_width(Self::WIDTH)
+            .show(ctx, |ui| {
+                if ui.button("Hide chat").clicked() {
+                    self.open = false;
+                }
+                ui.separator();
+                ScrollArea::vertical()
+                    .stick_to_bottom(true)
+                    .max_height(ui.available_height() - 40.)
+                    .show(ui, |ui| {
+                        for message in self.messages.iter() {
+                            ui.label(format!("{}: {}", message.name, message.text));
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let input = ui.add(
+                        TextEdit::singleline(&mut self.draft)
+                            .char_limit(Self::MAX_LENGTH)
+                            .hint_text("Message")
+                            .desired_width(Self::WIDTH - 80.),
+                    );
+                    let submitted =
+                        input.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+                    let text = self.draft.trim();
+                    let send = ui.add_enabled(!text.is_empty(), Button::new("Send"));
+                    if (submitted || send.clicked()) && !text.is_empty() {
+                        commands.spawn(PostMessageRequest {
+                            auth: session.into_header(),
+                            data: PostMessageData {
+                                text: text.to_owned(),
+                            },
+                        });
+                        self.draft.clear();
+                        input.request_focus();
+                    }
+                });
+            });
+    }
+}
+
+/// Shows posted message without waiting for the next status.
+fn on_post_response(
+    mut chat: ResMut<Chat>,
+    mut responses: EventReader<OnResponse<PostMessageRequest>>,
+) {
+    for OnResponse(message) in responses.iter() {
+        if chat.messages.iter().all(|known| known.id != message.id) {
+            chat.messages.push(message.clone());
+        }
+    }
+}
@@ -2,31 +2,61 @@
 
 use bevy::prelude::*;
 use bevy_egui::{
-    egui::{Button, CentralPanel, Frame, Vec2},
+    egui::{Align2, Area, Button, CentralPanel, Color32, Frame, Pos2, Ui, Vec2, Window},
     EguiContexts,
 };
-use durak_lib::game::table::Table;
+use durak_lib::game::{hand::Hand, player::Opponent, table::Table};
 
 use crate::{
-    network::{RetreatRequest, TakeRequest},
-    round::turn::Turn,
-    session::Session,
-    ui::utils::MARGIN,
+    network::{LeaveGameRequest, RetreatRequest, TakeRequest},
+    round::{turn::Turn, Discard, TurnTimer},
+    session::{Session, SpectatorSession},
+    ui::{chat::Chat, utils::MARGIN},
+    GameScreen,
 };
 
 const BUTTON_SIZE: Vec2 = Vec2::new(50., 50.);
 
+/// Vertical distance between the center of player's cards and the turn timer.
+const TIMER_OFFSET: f32 = 110.;
+
 pub fn display_ui(
     mut ctx: EguiContexts,
     mut commands: Commands,
     session: Res<Session>,
     table: Query<&Table>,
     turn: Option<Res<Turn>>,
+    mut chat: ResMut<Chat>,
+    mut state: ResMut<NextState<GameScreen>>,
+    mut confirm_leave: Local<bool>,
 ) {
     let ctx = ctx.ctx_mut();
+    chat.show(ctx, &mut commands, &session);
+    if *confirm_leave {
+        Window::new("Leave the game?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("Leaving counts as a loss.");
+                ui.horizontal(|ui| {
+                    if ui.button("Leave").clicked() {
+                        commands.spawn(LeaveGameRequest(session.into_header()));
+                        state.0 = Some(GameScreen::MainMenu);
+                        *confirm_leave = false;
+                    }
+                    if ui.button("Stay").clicked() {
+                        *confirm_leave = false;
+                    }
+                });
+            });
+    }
     CentralPanel::default()
         .frame(Frame::none().inner_margin(MARGIN))
         .show(ctx, |ui| {
+            if ui.add(Button::new("Leave").min_size(BUTTON_SIZE)).clicked() {
+                *confirm_leave = true;
+            }
             ui.add_space(ui.available_height() / 2. - BUTTON_SIZE.y / 2.);
             ui.horizontal(|ui| {
                 if let Some(turn) = turn {
@@ -53,3 +83,70 @@ pub fn display_ui(
             })
         });
 }
+
+/// Ui of the spectator, who can't make any moves.
+pub fn display_spectator_ui(
+    mut ctx: EguiContexts,
+    mut commands: Commands,
+    discard: Option<Res<Discard>>,
+    mut state: ResMut<NextState<GameScreen>>,
+) {
+    let ctx = ctx.ctx_mut();
+    CentralPanel::default()
+        .frame(Frame::none().inner_margin(MARGIN))
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Watching");
+                if let Some(discard) = discard {
+                    ui.label(format!("Discarded: {}", discard.0));
+                }
+            });
+            ui.add_space(ui.available_height() / 2. - BUTTON_SIZE.y / 2.);
+            if ui.add(Button::new("Leave").min_size(BUTTON_SIZE)).clicked() {
+                commands.remove_resource::<SpectatorSession>();
+                state.0 = Some(GameScreen::MainMenu);
+            }
+        });
+}
+
+/// Shows time left next to the cards of the player on turn.
+pub fn display_timer(
+    mut ctx: EguiContexts,
+    timer: Res<TurnTimer>,
+    session: Option<Res<Session>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    opponents: Query<(&Opponent, &GlobalTransform)>,
+    hand: Query<&GlobalTransform, With<Hand>>,
+) {
+    // Hand is at the bottom of the screen, opponents are at the top.
+    let position = if session.is_some_and(|session| session.id == timer.player) {
+        hand.single().translation() + Vec3::Y * TIMER_OFFSET
+    } else {
+        let Some((_, transform)) = opponents
+            .iter()
+            .find(|(opponent, _)| opponent.id == timer.player)
+        else {
+            return;
+        };
+        transform.translation() - Vec3::Y * TIMER_OFFSET
+    };
+    let (camera, camera_transform) = camera.single();
+    let Some(position) = camera.world_to_viewport(camera_transform, position) else {
+        return;
+    };
+    Area::new("turn timer")
+        .fixed_pos(Pos2::new(position.x, position.y))
+        .pivot(Align2::CENTER_CENTER)
+        .interactable(false)
+        .show(ctx.ctx_mut(), |ui| show_timer(ui, &timer));
+}
+
+/// Shows how much time the player on turn has left.
+fn show_timer(ui: &mut Ui, timer: &TurnTimer) {
+    let text = format!("{} s", timer.seconds_left);
+    if timer.seconds_left <= 5 {
+        ui.colored_label(Color32::RED, text);
+    } else {
+        ui.label(text);
+    }
+}
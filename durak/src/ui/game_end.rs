@@ -7,20 +7,28 @@ use super::UiContext;
 pub fn show_game_end_ui(
     mut ctx: UiContext,
     mut events: EventReader<GameEnded>,
-    mut winner: Local<Option<String>>,
+    mut winner: Local<Option<(String, Option<String>)>>,
     mut next_state: ResMut<NextState<GameScreen>>,
 ) {
-    if let Some(GameEnded { winner_name, .. }) = events.iter().last() {
-        *winner = Some(winner_name.clone());
+    if let Some(GameEnded {
+        winner_name,
+        forfeited_name,
+        ..
+    }) = events.iter().last()
+    {
+        *winner = Some((winner_name.clone(), forfeited_name.clone()));
     };
 
-    if let Some(winner_name) = winner.clone() {
+    if let Some((winner_name, forfeited_name)) = winner.clone() {
         ctx.show(|ui| {
             ui.vertical_centered(|ui| {
                 ui.add_space(ui.available_height() / 3.);
                 ui.spacing_mut().item_spacing.y = 50.;
 
                 ui.label("Game over!");
+                if let Some(forfeited_name) = forfeited_name {
+                    ui.label(format!("{forfeited_name} left the game"));
+                }
                 ui.label(format!("{winner_name} won"));
                 if ui.button("Leave to menu").clicked() {
                     *winner = None;
@@ -1,12 +1,18 @@
+mod browse_lobbies;
 mod create_game;
 mod join_game;
 mod lobby;
 mod main;
+mod outdated;
+mod quick_play;
+mod spectate;
 
 use crate::GameScreen;
 
 use self::{
-    create_game::CreateGameScreen, join_game::JoinGameScreen, lobby::LobbyScreen, main::MainScreen,
+    browse_lobbies::BrowseLobbiesScreen, create_game::CreateGameScreen, join_game::JoinGameScreen,
+    lobby::LobbyScreen, main::MainScreen, outdated::OutdatedScreen, quick_play::QuickPlayScreen,
+    spectate::SpectateScreen,
 };
 use bevy::prelude::*;
 
@@ -18,7 +24,11 @@ impl Plugin for MainMenuPlugin {
             .add_plugins(MainScreen)
             .add_plugins(CreateGameScreen)
             .add_plugins(JoinGameScreen)
+            .add_plugins(BrowseLobbiesScreen)
+            .add_plugins(QuickPlayScreen)
             .add_plugins(LobbyScreen)
+            .add_plugins(SpectateScreen)
+            .add_plugins(OutdatedScreen)
             .add_systems(OnEnter(GameScreen::MainMenu), set_screen)
             .add_systems(OnExit(GameScreen::MainMenu), reset_screen);
     }
@@ -38,6 +48,13 @@ enum CurrentScreen {
     MainMenu,
     CreateGame,
     JoinGame,
+    BrowseLobbies,
+    /// Player waits in the matchmaking queue.
+    QuickPlay,
     Lobby,
+    /// Spectator waits for the game to start.
+    Spectate,
+    /// Server speaks another protocol version, so the game can't be played.
+    Outdated,
     None,
 }
@@ -0,0 +1,162 @@
+//! Browser of public lobbies.
+
+use bevy::prelude::*;
+use bevy_egui::egui::{Align, Button, Color32, Frame, Layout, Margin, ScrollArea, Ui, Vec2};
+use durak_lib::network::{JoinGameData, LobbySummary};
+
+use crate::{
+    network::{JoinGameRequest, LobbiesRequest, OnError, OnResponse},
+    persistence::Configuration,
+    ui::{
+        notification::Notifications,
+        utils::{BUTTON_SIZE, MARGIN},
+        UiContext,
+    },
+};
+
+use super::{join_game::ScreenState as JoinScreenState, CurrentScreen};
+
+pub struct BrowseLobbiesScreen;
+
+impl Plugin for BrowseLobbiesScreen {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Lobbies>()
+            .add_systems(
+                Update,
+                (
+                    display.run_if(in_state(CurrentScreen::BrowseLobbies)),
+                    on_lobbies_response,
+                    on_lobbies_error,
+                ),
+            )
+            .add_systems(OnEnter(CurrentScreen::BrowseLobbies), refresh);
+    }
+}
+
+/// Lobbies received from the server, `None` while they are loading.
+#[derive(Resource, Debug, Default)]
+struct Lobbies(Option<Vec<LobbySummary>>);
+
+fn refresh(mut commands: Commands, mut lobbies: ResMut<Lobbies>) {
+    lobbies.0 = None;
+    commands.spawn(LobbiesRequest);
+}
+
+fn display(
+    mut ctx: UiContext,
+    mut commands: Commands,
+    mut lobbies: ResMut<Lobbies>,
+    mut join_state: ResMut<JoinScreenState>,
+    config: Res<Configuration>,
+    mut next_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let mut selected = None;
+    ctx.margin(MARGIN).show(|ui: &mut Ui| {
+        ui.vertical_centered_justified(|ui| {
+            let list_height = ui.available_height() - BUTTON_SIZE.y - MARGIN;
+            ui.allocate_ui(
+                Vec2::new(ui.available_width(), list_height),
+                |ui| match &lobbies.0 {
+                    None => {
+                        ui.centered_and_justified(|ui| ui.label("Loading..."));
+                    }
+                    Some(list) if list.is_empty() => {
+                        ui.centered_and_justified(|ui| ui.label("No public games right now"));
+                    }
+                    Some(list) => {
+                        ScrollArea::vertical().show(ui, |ui| {
+                            for lobby in list {
+                                if lobby_entry(ui, lobby) {
+                                    selected = Some(lobby.clone());
+                                }
+                            }
+                        });
+                    }
+                },
+            );
+            ui.add_space(ui.available_height() - BUTTON_SIZE.y);
+            ui.horizontal(|ui| {
+                if ui
+                    .add(Button::new("Return").min_size(BUTTON_SIZE))
+                    .clicked()
+                {
+                    next_state.0 = Some(CurrentScreen::MainMenu);
+                }
+                ui.add_space(ui.available_width() - BUTTON_SIZE.x);
+                if ui
+                    .add_enabled(
+                        lobbies.0.is_some(),
+                        Button::new("Refresh").min_size(BUTTON_SIZE),
+                    )
+                    .clicked()
+                {
+                    lobbies.0 = None;
+                    commands.spawn(LobbiesRequest);
+                }
+            });
+        });
+    });
+
+    let Some(lobby) = selected else {
+        return;
+    };
+    join_state.id = lobby.id.to_string();
+    join_state.password = String::new();
+    if lobby.has_password {
+        // Password has to be entered on the join screen.
+        next_state.0 = Some(CurrentScreen::JoinGame);
+    } else {
+        commands.spawn(JoinGameRequest(JoinGameData {
+            id: lobby.id,
+            name: config.name.clone(),
+            password: String::new(),
+        }));
+        next_state.0 = Some(CurrentScreen::Lobby);
+    }
+}
+
+/// Shows the lobby and returns `true` if player wants to join it.
+fn lobby_entry(ui: &mut Ui, lobby: &LobbySummary) -> bool {
+    let mut join = false;
+    Frame::none()
+        .outer_margin(Margin::symmetric(0., MARGIN / 2.))
+        .inner_margin(MARGIN / 2.)
+        .fill(Color32::from_gray(40))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(lobby.host.as_str());
+                ui.add_space(MARGIN);
+                ui.label(format!("{}/{}", lobby.players, lobby.rules.players));
+                ui.add_space(MARGIN);
+                ui.label(lobby.rules.to_string());
+                if lobby.has_password {
+                    ui.add_space(MARGIN);
+                    ui.label("password");
+                }
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    join = ui.add(Button::new("Join").min_size(BUTTON_SIZE)).clicked();
+                });
+            });
+        });
+    join
+}
+
+fn on_lobbies_response(
+    mut events: EventReader<OnResponse<LobbiesRequest>>,
+    mut lobbies: ResMut<Lobbies>,
+) {
+    if let Some(OnResponse(list)) = events.iter().last() {
+        lobbies.0 = Some(list.clone());
+    }
+}
+
+fn on_lobbies_error(
+    mut events: EventReader<OnError<LobbiesRequest>>,
+    mut lobbies: ResMut<Lobbies>,
+    mut notifications: ResMut<Notifications>,
+) {
+    if let Some(OnError { error, .. }) = events.iter().last() {
+        notifications.push(format!("Failed to load games: {}", error.message));
+        lobbies.0 = Some(Vec::new());
+    }
+}
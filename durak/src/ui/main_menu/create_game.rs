@@ -1,9 +1,12 @@
 use bevy::prelude::*;
-use bevy_egui::egui::{Button, Ui, Vec2};
-use durak_lib::network::{CreateGameData, CreateGameResponse};
+use bevy_egui::egui::{Button, Slider, Ui, Vec2};
+use durak_lib::{
+    game::rules::Rules,
+    network::{CreateGameData, CreateGameResponse},
+};
 
 use crate::{
-    network::{CreateGameRequest, OnResponse},
+    network::{CreateGameRequest, OnError, OnResponse},
     persistence::Configuration,
     session::Session,
     ui::{
@@ -24,6 +27,7 @@ impl Plugin for CreateGameScreen {
                 (
                     display.run_if(in_state(CurrentScreen::CreateGame)),
                     on_create_response,
+                    on_create_error,
                 ),
             )
             .add_systems(
@@ -54,6 +58,15 @@ fn display(
 
             ui.label("Password:");
             ui.add(BigTextInput::new(&mut state.password));
+            ui.add_space(25.);
+            ui.checkbox(&mut state.public, "List in public games");
+            let mut limited = state.turn_time.is_some();
+            if ui.checkbox(&mut limited, "Limit time per turn").changed() {
+                state.turn_time = limited.then_some(ScreenState::DEFAULT_TURN_TIME);
+            }
+            if let Some(turn_time) = &mut state.turn_time {
+                ui.add(Slider::new(turn_time, 10..=120).suffix(" s"));
+            }
             ui.add_space(ui.available_height() - BUTTON_SIZE.y);
             ui.horizontal(|ui| {
                 if ui
@@ -70,6 +83,11 @@ fn display(
                     commands.spawn(CreateGameRequest(CreateGameData {
                         name: config.name.clone(),
                         password: state.password.clone(),
+                        rules: Rules {
+                            turn_time: state.turn_time,
+                            ..Rules::default()
+                        },
+                        public: state.public,
                     }));
                     next_state.0 = Some(CurrentScreen::Lobby);
                 }
@@ -82,6 +100,7 @@ fn on_create_response(
     mut commands: Commands,
     mut events: EventReader<OnResponse<CreateGameRequest>>,
     config: Res<Configuration>,
+    state: Res<ScreenState>,
     mut next_menu_state: ResMut<NextState<CurrentScreen>>,
 ) {
     if let Some(OnResponse(response)) = events.iter().next() {
@@ -95,8 +114,8 @@ fn on_create_response(
                     name: config.name.clone(),
                     id: *player_id,
                     game: *game_id,
-                    token: *token,
-                    is_host: true,
+                    token: token.clone(),
+                    password: Some(state.password.clone()),
                 });
                 next_menu_state.0 = Some(CurrentScreen::Lobby);
             }
@@ -104,7 +123,24 @@ fn on_create_response(
     }
 }
 
+fn on_create_error(
+    mut events: EventReader<OnError<CreateGameRequest>>,
+    mut next_menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if events.iter().next().is_some() {
+        next_menu_state.0 = Some(CurrentScreen::CreateGame);
+    }
+}
+
 #[derive(Resource, Debug, Clone, Default)]
 struct ScreenState {
     pub password: String,
+    /// Whether the game is listed in the lobby browser.
+    pub public: bool,
+    /// Seconds each player has to act, `None` if turns are not limited.
+    pub turn_time: Option<u16>,
+}
+
+impl ScreenState {
+    const DEFAULT_TURN_TIME: u16 = 30;
 }
@@ -2,12 +2,13 @@ use bevy::prelude::*;
 use bevy_egui::egui::{Button, Vec2};
 use durak_lib::{
     identifiers::GameId,
-    network::{JoinGameData, JoinGameResponse},
+    invite::InviteLink,
+    network::{JoinGameData, JoinGameResponse, SpectateData},
 };
 
 use crate::{
-    network::{JoinGameRequest, OnResponse},
-    persistence::Configuration,
+    network::{JoinGameRequest, OnError, OnResponse, SpectateRequest, VersionRequest},
+    persistence::{is_valid_server_address, Configuration},
     session::Session,
     ui::{
         utils::{BigTextInput, BUTTON_SIZE, MARGIN},
@@ -22,23 +23,88 @@ pub struct JoinGameScreen;
 impl Plugin for JoinGameScreen {
     fn build(&self, app: &mut App) {
         app.init_resource::<ScreenState>()
+            .add_systems(Startup, read_invite_argument)
             .add_systems(
                 Update,
                 (
                     display.run_if(in_state(CurrentScreen::JoinGame)),
+                    open_pending_invite.run_if(
+                        resource_exists::<PendingInvite>()
+                            .and_then(in_state(CurrentScreen::MainMenu)),
+                    ),
                     on_join_response,
+                    on_join_error,
                 ),
             )
             .add_systems(
-                OnEnter(CurrentScreen::JoinGame),
-                |mut screen: ResMut<ScreenState>| {
-                    screen.id = String::new();
-                    screen.password = String::new();
+                OnTransition {
+                    from: CurrentScreen::MainMenu,
+                    to: CurrentScreen::JoinGame,
                 },
+                reset_screen,
             );
     }
 }
 
+/// Invite that should be opened on the join screen.
+#[derive(Resource, Debug)]
+struct PendingInvite(InviteLink);
+
+/// Accepts invite link passed as the first command-line argument.
+fn read_invite_argument(mut commands: Commands) {
+    let Some(argument) = std::env::args().nth(1) else {
+        return;
+    };
+    match argument.parse() {
+        Ok(invite) => commands.insert_resource(PendingInvite(invite)),
+        Err(err) => warn!("ignoring argument `{argument}`: {err}"),
+    }
+}
+
+fn open_pending_invite(mut next_state: ResMut<NextState<CurrentScreen>>) {
+    next_state.0 = Some(CurrentScreen::JoinGame);
+}
+
+fn reset_screen(
+    mut commands: Commands,
+    mut state: ResMut<ScreenState>,
+    mut config: ResMut<Configuration>,
+    invite: Option<Res<PendingInvite>>,
+) {
+    state.id = String::new();
+    state.password = String::new();
+    if let Some(invite) = invite {
+        apply_invite(invite.0.clone(), &mut state, &mut config, &mut commands);
+        commands.remove_resource::<PendingInvite>();
+    }
+}
+
+/// Fills the screen from the invite and switches to the server that hosts the game.
+fn apply_invite(
+    invite: InviteLink,
+    state: &mut ScreenState,
+    config: &mut Configuration,
+    commands: &mut Commands,
+) {
+    state.id = invite.id.to_string();
+    state.password = invite.password;
+    let Some(server) = invite.server else {
+        return;
+    };
+    if server == config.server_address {
+        return;
+    }
+    if !is_valid_server_address(&server) {
+        warn!("invite link contains invalid server address `{server}`");
+        return;
+    }
+    info!("switching to server `{server}` from the invite link");
+    config.server_address = server;
+    let _ = config.save();
+    // Another server may speak another protocol.
+    commands.spawn(VersionRequest);
+}
+
 fn display(
     mut ctx: UiContext,
     mut commands: Commands,
@@ -61,8 +127,14 @@ fn display(
                 for column in columns.iter_mut() {
                     column.spacing_mut().item_spacing = Vec2::new(0., 10.);
                 }
-                columns[0].label("Id:");
-                columns[0].add(BigTextInput::new(&mut state.id));
+                columns[0].label("Join code or invite link:");
+                if columns[0].add(BigTextInput::new(&mut state.id)).changed() {
+                    match state.id.parse::<InviteLink>() {
+                        Ok(invite) => apply_invite(invite, &mut state, &mut config, &mut commands),
+                        // Codes are case-insensitive, but displayed the way host sees them.
+                        Err(_) => state.id = state.id.to_uppercase(),
+                    }
+                }
                 columns[1].label("Password:");
                 columns[1].add(BigTextInput::new(&mut state.password));
             });
@@ -75,9 +147,22 @@ fn display(
                     next_state.0 = Some(CurrentScreen::MainMenu);
                 }
 
-                ui.add_space(ui.available_width() - BUTTON_SIZE.x);
+                let spacing = ui.spacing().item_spacing.x;
+                ui.add_space(ui.available_width() - BUTTON_SIZE.x * 2. - spacing);
 
-                let id: Result<GameId, _> = state.id.parse();
+                let id: Result<GameId, _> = state.id.trim().parse();
+                if ui
+                    .add_enabled(id.is_ok(), Button::new("Watch").min_size(BUTTON_SIZE))
+                    .clicked()
+                {
+                    if let Ok(id) = id {
+                        commands.spawn(SpectateRequest(SpectateData {
+                            id,
+                            password: state.password.clone(),
+                        }));
+                        next_state.0 = Some(CurrentScreen::Spectate);
+                    };
+                }
                 if ui
                     .add_enabled(id.is_ok(), Button::new("Join").min_size(BUTTON_SIZE))
                     .clicked()
@@ -100,6 +185,7 @@ fn on_join_response(
     mut commands: Commands,
     mut events: EventReader<OnResponse<JoinGameRequest>>,
     config: Res<Configuration>,
+    state: Res<ScreenState>,
     mut next_menu_state: ResMut<NextState<CurrentScreen>>,
 ) {
     if let Some(OnResponse(response)) = events.iter().next() {
@@ -113,20 +199,28 @@ fn on_join_response(
                     name: config.name.clone(),
                     id: *player_id,
                     game: *game_id,
-                    token: *token,
-                    is_host: false,
+                    token: token.clone(),
+                    password: Some(state.password.clone()),
                 });
                 next_menu_state.0 = Some(CurrentScreen::Lobby);
             }
-            _ => {
-                next_menu_state.0 = Some(CurrentScreen::MainMenu);
-            }
         }
     }
 }
 
+/// Returns to join screen, so player may fix entered data.
+fn on_join_error(
+    mut events: EventReader<OnError<JoinGameRequest>>,
+    mut next_menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if events.iter().next().is_some() {
+        next_menu_state.0 = Some(CurrentScreen::JoinGame);
+    }
+}
+
+/// Data entered on the join screen.
 #[derive(Resource, Debug, Clone, Default)]
-struct ScreenState {
+pub(super) struct ScreenState {
     pub id: String,
     pub password: String,
 }
@@ -1,18 +1,22 @@
 use std::time::Duration;
 
 use bevy::{prelude::*, time::common_conditions::on_timer};
-use bevy_egui::egui::{
-    Align, Button, Color32, Direction, Frame, Label, Layout, Margin, Sense, Ui, Vec2,
-};
+use bevy_egui::egui::{Align, Button, Color32, Frame, Label, Layout, Margin, Sense, Ui, Vec2};
 use durak_lib::{
-    identifiers::PlayerId,
-    status::{lobby::LobbyStatus, StatusResponse},
+    errors::{ApiError, AuthFailed},
+    status::{lobby::LobbyStatus, PlayerData, StatusResponse},
 };
 
 use crate::{
-    network::{LeaveGameRequest, OnResponse, StartGameRequest, StatusRequest},
+    network::{
+        KickRequest, LeaveGameRequest, OnError, OnResponse, ReadyRequest, StartGameRequest,
+        StatusRequest, SwapSeatsRequest,
+    },
+    persistence::Configuration,
     session::Session,
     ui::{
+        chat::Chat,
+        notification::Notifications,
         utils::{BUTTON_SIZE, MARGIN},
         UiContext,
     },
@@ -38,9 +42,11 @@ impl Plugin for LobbyScreen {
                         .or_else(not(resource_exists::<LobbyStatus>())),
                 ),
                 on_status_response,
+                on_status_error,
             )
                 .run_if(in_state(CurrentScreen::Lobby)),
-        );
+        )
+        .add_systems(OnEnter(CurrentScreen::Lobby), reset_chat);
     }
 }
 
@@ -49,16 +55,39 @@ fn display(
     mut commands: Commands,
     status: Res<LobbyStatus>,
     session: Res<Session>,
+    config: Res<Configuration>,
+    mut notifications: ResMut<Notifications>,
     mut menu_state: ResMut<NextState<CurrentScreen>>,
+    mut chat: ResMut<Chat>,
 ) {
+    // Host may change when the previous one leaves.
+    let is_session_host = status.host == Some(session.id);
+    chat.show(ctx.ctx_mut(), &mut commands, &session);
     ctx.show(|ui: &mut Ui| {
         ui.vertical_centered_justified(|ui| {
             Frame::none().fill(Color32::from_gray(15)).show(ui, |ui| {
                 let (rect, _) =
                     ui.allocate_exact_size(Vec2::new(ui.available_width(), 75.), Sense::hover());
                 ui.allocate_ui_at_rect(rect, |ui| {
-                    ui.with_layout(Layout::centered_and_justified(Direction::TopDown), |ui| {
-                        ui.label(format!("Game #{}", session.game));
+                    ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+                        ui.add_space(MARGIN);
+                        ui.label(format!("Join code: {}", session.game));
+                        ui.add_space(ui.available_width() - BUTTON_SIZE.x - MARGIN);
+                        // Password isn't known after the session is restored.
+                        let link = session.invite_link(&config.server_address);
+                        if ui
+                            .add_enabled(
+                                link.is_some(),
+                                Button::new("Copy invite link").min_size(BUTTON_SIZE),
+                            )
+                            .on_disabled_hover_text("Password of the game is unknown")
+                            .clicked()
+                        {
+                            if let Some(link) = link {
+                                ui.output_mut(|output| output.copied_text = link.to_string());
+                                notifications.push("Invite link copied");
+                            }
+                        }
                     });
                 });
             });
@@ -71,8 +100,44 @@ fn display(
                     Sense::click(),
                 );
                 ui.allocate_ui_at_rect(rect, |ui| {
-                    for player in status.players.iter() {
-                        player_entry(ui, player.id, &player.name, session.is_host);
+                    for seat in 0..status.seats {
+                        let Some(player) = status.players.iter().find(|p| p.seat == seat) else {
+                            empty_seat(ui, seat);
+                            continue;
+                        };
+                        let is_host = status.host == Some(player.id);
+                        let is_ready = status.is_ready(player.id);
+                        let action = player_entry(
+                            ui,
+                            player,
+                            status.seats,
+                            is_host,
+                            is_ready,
+                            is_session_host,
+                        );
+                        match action {
+                            Some(PlayerAction::Kick) => {
+                                commands.spawn(KickRequest {
+                                    auth: session.into_header(),
+                                    player: player.id,
+                                });
+                            }
+                            Some(PlayerAction::MoveUp) => {
+                                commands.spawn(SwapSeatsRequest {
+                                    auth: session.into_header(),
+                                    first: seat,
+                                    second: seat - 1,
+                                });
+                            }
+                            Some(PlayerAction::MoveDown) => {
+                                commands.spawn(SwapSeatsRequest {
+                                    auth: session.into_header(),
+                                    first: seat,
+                                    second: seat + 1,
+                                });
+                            }
+                            None => {}
+                        }
                     }
                 });
             });
@@ -88,11 +153,23 @@ fn display(
                                 commands.spawn(LeaveGameRequest(session.into_header()));
                                 menu_state.0 = Some(CurrentScreen::MainMenu);
                             }
+                            let label = if status.is_ready(session.id) {
+                                "Not ready"
+                            } else {
+                                "Ready"
+                            };
+                            if ui.add(Button::new(label).min_size(BUTTON_SIZE)).clicked() {
+                                commands.spawn(ReadyRequest(session.into_header()));
+                            }
                             ui.add_space(ui.available_width() - BUTTON_SIZE.x);
+                            let label = match status.countdown {
+                                Some(left) if left > 0 => format!("Start in {left}"),
+                                _ => String::from("Start"),
+                            };
                             if ui
                                 .add_enabled(
-                                    status.can_start() && session.is_host,
-                                    Button::new("Start").min_size(BUTTON_SIZE),
+                                    status.can_start() && is_session_host,
+                                    Button::new(label).min_size(BUTTON_SIZE),
                                 )
                                 .clicked()
                             {
@@ -109,30 +186,102 @@ fn request_status(mut commands: Commands, session: Res<Session>) {
     commands.spawn(StatusRequest(session.into_header()));
 }
 
+fn reset_chat(mut chat: ResMut<Chat>) {
+    chat.reset();
+}
+
 fn display_loading(mut ctx: UiContext) {
     ctx.show(|ui| {
         ui.centered_and_justified(|ui| ui.label("Waiting for server..."));
     })
 }
 
-fn player_entry(ui: &mut Ui, player: PlayerId, name: &str, is_host: bool) {
-    const HEIGHT: f32 = 100.;
+/// Action chosen by the host for one of the players.
+enum PlayerAction {
+    Kick,
+    MoveUp,
+    MoveDown,
+}
+
+const ENTRY_HEIGHT: f32 = 100.;
+
+/// Shows the player at their seat.
+///
+/// Host may kick other players and move anyone to the neighbouring seat.
+fn player_entry(
+    ui: &mut Ui,
+    player: &PlayerData,
+    seats: u8,
+    is_host: bool,
+    is_ready: bool,
+    manage: bool,
+) -> Option<PlayerAction> {
+    let mut action = None;
+    seat_frame(ui, player.seat, |ui| {
+        if is_ready {
+            ui.colored_label(Color32::GREEN, "✔");
+        } else {
+            ui.weak("…");
+        }
+        ui.label(player.name.as_str());
+        if is_host {
+            ui.label("(host)");
+        }
+        if !manage {
+            return;
+        }
+        ui.add_space(ui.available_width() - ENTRY_HEIGHT - BUTTON_SIZE.x - MARGIN);
+        ui.vertical(|ui| {
+            let size = Vec2::new(BUTTON_SIZE.x, ENTRY_HEIGHT / 2.);
+            if ui
+                .add_enabled(player.seat > 0, Button::new("Up").min_size(size))
+                .clicked()
+            {
+                action = Some(PlayerAction::MoveUp);
+            }
+            if ui
+                .add_enabled(player.seat + 1 < seats, Button::new("Down").min_size(size))
+                .clicked()
+            {
+                action = Some(PlayerAction::MoveDown);
+            }
+        });
+        if !is_host
+            && ui
+                .add(Button::new("Kick").min_size(Vec2::splat(ENTRY_HEIGHT)))
+                .clicked()
+        {
+            action = Some(PlayerAction::Kick);
+        }
+    });
+    action
+}
+
+/// Shows seat that is not taken by anyone.
+fn empty_seat(ui: &mut Ui, seat: u8) {
+    seat_frame(ui, seat, |ui| {
+        ui.weak("Empty seat");
+    });
+}
+
+fn seat_frame(ui: &mut Ui, seat: u8, add_contents: impl FnOnce(&mut Ui)) {
     Frame::none()
         .outer_margin(Margin::symmetric(MARGIN, MARGIN / 2.))
         .fill(Color32::from_gray(40))
         .show(ui, |ui| {
-            let (rect, _) =
-                ui.allocate_exact_size(Vec2::new(ui.available_width(), HEIGHT), Sense::hover());
+            let (rect, _) = ui.allocate_exact_size(
+                Vec2::new(ui.available_width(), ENTRY_HEIGHT),
+                Sense::hover(),
+            );
             ui.allocate_ui_at_rect(rect, |ui| {
                 ui.horizontal(|ui| {
                     Frame::none().fill(Color32::from_gray(60)).show(ui, |ui| {
-                        ui.add_sized(Vec2::splat(HEIGHT), Label::new(player.to_string()));
+                        ui.add_sized(
+                            Vec2::splat(ENTRY_HEIGHT),
+                            Label::new(format!("#{}", seat + 1)),
+                        );
                     });
-                    ui.label(name);
-                    if is_host {
-                        ui.add_space(ui.available_width() - HEIGHT);
-                        ui.add(Button::new("Kick").min_size(Vec2::splat(HEIGHT)));
-                    }
+                    add_contents(ui);
                 });
             });
         });
@@ -143,6 +292,7 @@ fn on_status_response(
     mut responses: EventReader<OnResponse<StatusRequest>>,
     mut state: ResMut<NextState<GameScreen>>,
     mut started: EventWriter<GameStarted>,
+    mut chat: ResMut<Chat>,
 ) {
     let Some(OnResponse(status)) = responses.iter().last() else {
         return;
@@ -150,6 +300,7 @@ fn on_status_response(
 
     match status {
         StatusResponse::Lobby(lobby) => {
+            chat.update(&lobby.chat);
             commands.insert_resource(lobby.clone());
         }
         StatusResponse::Round(round) => {
@@ -160,9 +311,29 @@ fn on_status_response(
             state.0 = Some(GameScreen::Round);
             commands.remove_resource::<LobbyStatus>();
         }
-        StatusResponse::Finished(_) | StatusResponse::Error(_) => {
+        StatusResponse::Finished(_) => {
             state.0 = Some(GameScreen::MainMenu);
             commands.remove_resource::<LobbyStatus>();
         }
     }
 }
+
+/// Leaves the lobby if it is no longer accessible.
+fn on_status_error(
+    mut commands: Commands,
+    mut errors: EventReader<OnError<StatusRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let Some(OnError { error, .. }) = errors.iter().last() else {
+        return;
+    };
+    if let ApiError::AuthFailed(AuthFailed::Kicked) = error.error {
+        notifications.push("You were removed from the game by the host");
+    } else {
+        notifications.push(format!("Lost access to the game: {}", error.message));
+    }
+    menu_state.0 = Some(CurrentScreen::MainMenu);
+    commands.remove_resource::<LobbyStatus>();
+    commands.remove_resource::<Session>();
+}
@@ -1,10 +1,14 @@
 use bevy::{app::AppExit, prelude::*};
 use bevy_egui::egui::{Button, Ui, Vec2};
 
-use crate::ui::{
-    main_menu::CurrentScreen,
-    utils::{BUTTON_SIZE, MARGIN},
-    UiContext,
+use crate::{
+    persistence::SavedSession,
+    session::Session,
+    ui::{
+        main_menu::CurrentScreen,
+        utils::{BUTTON_SIZE, MARGIN},
+        UiContext,
+    },
 };
 
 pub struct MainScreen;
@@ -17,16 +21,38 @@ impl Plugin for MainScreen {
 
 fn display(
     mut ctx: UiContext,
+    mut commands: Commands,
     mut exit: EventWriter<AppExit>,
     mut menu_state: ResMut<NextState<CurrentScreen>>,
+    saved: Option<Res<SavedSession>>,
 ) {
     ctx.show(move |ui: &mut Ui| {
-        let height = ui.available_size().y / 2. - (BUTTON_SIZE.y * 3. + MARGIN * 2.) / 2.;
+        let buttons = if saved.is_some() { 6. } else { 5. };
+        let height =
+            ui.available_size().y / 2. - (BUTTON_SIZE.y * buttons + MARGIN * (buttons - 1.)) / 2.;
         ui.add_space(height);
 
         ui.vertical_centered(|ui| {
             ui.style_mut().spacing.item_spacing = Vec2::new(0., MARGIN);
 
+            if let Some(saved) = saved {
+                if ui
+                    .add(Button::new("Rejoin game").min_size(BUTTON_SIZE))
+                    .clicked()
+                {
+                    commands.insert_resource(Session::restore(&saved));
+                    commands.remove_resource::<SavedSession>();
+                    // Lobby switches to the round on the first status if the game is started.
+                    menu_state.0 = Some(CurrentScreen::Lobby);
+                }
+            }
+
+            if ui
+                .add(Button::new("Quick play").min_size(BUTTON_SIZE))
+                .clicked()
+            {
+                menu_state.0 = Some(CurrentScreen::QuickPlay);
+            }
             if ui
                 .add(Button::new("Create").min_size(BUTTON_SIZE))
                 .clicked()
@@ -36,6 +62,12 @@ fn display(
             if ui.add(Button::new("Join").min_size(BUTTON_SIZE)).clicked() {
                 menu_state.0 = Some(CurrentScreen::JoinGame);
             }
+            if ui
+                .add(Button::new("Browse").min_size(BUTTON_SIZE))
+                .clicked()
+            {
+                menu_state.0 = Some(CurrentScreen::BrowseLobbies);
+            }
             if ui.add(Button::new("Quit").min_size(BUTTON_SIZE)).clicked() {
                 exit.send(AppExit);
             }
@@ -0,0 +1,117 @@
+//! Protocol version check and screen that blocks play when it fails.
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_egui::egui::{Button, Ui, Vec2};
+use durak_lib::network::PROTOCOL_VERSION;
+
+use crate::{
+    network::{OnError, OnResponse, VersionRequest},
+    ui::{
+        utils::{BUTTON_SIZE, MARGIN},
+        UiContext,
+    },
+};
+
+use super::CurrentScreen;
+
+pub struct OutdatedScreen;
+
+impl Plugin for OutdatedScreen {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, request_version)
+            .add_systems(Update, (on_version_response, on_version_error))
+            .add_systems(OnEnter(CurrentScreen::MainMenu), keep_blocked)
+            .add_systems(Update, display.run_if(in_state(CurrentScreen::Outdated)));
+    }
+}
+
+/// Server that speaks incompatible protocol.
+#[derive(Resource, Debug, Clone)]
+struct IncompatibleServer {
+    /// Protocol version of the server, if server reported it.
+    protocol: Option<u32>,
+}
+
+fn request_version(mut commands: Commands) {
+    commands.spawn(VersionRequest);
+}
+
+fn on_version_response(
+    mut commands: Commands,
+    mut events: EventReader<OnResponse<VersionRequest>>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let Some(OnResponse(version)) = events.iter().next() else {
+        return;
+    };
+    if !version.is_compatible() {
+        warn!(
+            "server speaks protocol v{}, while client speaks v{PROTOCOL_VERSION}",
+            version.protocol
+        );
+        commands.insert_resource(IncompatibleServer {
+            protocol: Some(version.protocol),
+        });
+        menu_state.0 = Some(CurrentScreen::Outdated);
+    }
+}
+
+/// Servers that predate version endpoint reject the request.
+fn on_version_error(
+    mut commands: Commands,
+    mut events: EventReader<OnError<VersionRequest>>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if events.iter().next().is_some() {
+        warn!("server doesn't report protocol version");
+        commands.insert_resource(IncompatibleServer { protocol: None });
+        menu_state.0 = Some(CurrentScreen::Outdated);
+    }
+}
+
+/// Prevents returning to main menu after incompatible server was detected.
+fn keep_blocked(
+    server: Option<Res<IncompatibleServer>>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if server.is_some() {
+        menu_state.0 = Some(CurrentScreen::Outdated);
+    }
+}
+
+fn display(mut ctx: UiContext, server: Res<IncompatibleServer>, mut exit: EventWriter<AppExit>) {
+    ctx.margin(MARGIN).show(|ui: &mut Ui| {
+        ui.vertical_centered(|ui| {
+            ui.spacing_mut().item_spacing = Vec2::new(0., 10.);
+
+            ui.heading("Please update");
+            ui.add_space(25.);
+            ui.label("This version of the game can't play on the selected server.");
+            match server.protocol {
+                Some(protocol) if protocol > PROTOCOL_VERSION => {
+                    ui.label("The server is newer than the game, please update the game.");
+                }
+                Some(_) => {
+                    ui.label(
+                        "The server is older than the game, please ask its owner to update it.",
+                    );
+                }
+                None => {
+                    ui.label("The server is too old, please ask its owner to update it.");
+                }
+            }
+            ui.label(format!(
+                "Game protocol: v{PROTOCOL_VERSION}, server protocol: {}",
+                server.protocol.map_or_else(
+                    || String::from("unknown"),
+                    |protocol| format!("v{protocol}")
+                )
+            ));
+
+            ui.add_space(ui.available_height() - BUTTON_SIZE.y);
+            if ui.add(Button::new("Quit").min_size(BUTTON_SIZE)).clicked() {
+                exit.send(AppExit);
+            }
+        });
+    });
+}
@@ -0,0 +1,191 @@
+//! Screen shown while the player waits in the matchmaking queue.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_egui::egui::{Button, Ui};
+use durak_lib::{
+    game::rules::Rules,
+    matchmaking::{QueueData, QueueStatus, Ticket},
+    network::JoinGameResponse,
+};
+
+use crate::{
+    network::{EnterQueueRequest, LeaveQueueRequest, OnError, OnResponse, QueueStatusRequest},
+    persistence::Configuration,
+    session::Session,
+    ui::{
+        notification::Notifications,
+        utils::{BUTTON_SIZE, MARGIN},
+        UiContext,
+    },
+};
+
+use super::CurrentScreen;
+
+pub struct QuickPlayScreen;
+
+impl Plugin for QuickPlayScreen {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QueueState>()
+            .add_systems(
+                Update,
+                (
+                    display,
+                    request_status.run_if(on_timer(Duration::from_secs(1))),
+                )
+                    .run_if(in_state(CurrentScreen::QuickPlay)),
+            )
+            .add_systems(
+                Update,
+                (on_queue_response, on_status_response, on_queue_error),
+            )
+            .add_systems(OnEnter(CurrentScreen::QuickPlay), enter_queue);
+    }
+}
+
+/// Place of the player in the queue.
+#[derive(Resource, Debug, Default)]
+struct QueueState {
+    /// Whether the player wants to play, `false` after cancelling.
+    active: bool,
+    /// Ticket received from the server, `None` until the player is queued.
+    ticket: Option<Ticket>,
+    /// Number of players waiting for the same game.
+    players: u8,
+}
+
+fn enter_queue(mut commands: Commands, mut state: ResMut<QueueState>, config: Res<Configuration>) {
+    *state = QueueState {
+        active: true,
+        ..default()
+    };
+    commands.spawn(EnterQueueRequest(QueueData {
+        name: config.name.clone(),
+        rules: Rules::default(),
+    }));
+}
+
+fn display(
+    mut ctx: UiContext,
+    mut commands: Commands,
+    mut state: ResMut<QueueState>,
+    mut next_state: ResMut<NextState<CurrentScreen>>,
+) {
+    ctx.margin(MARGIN).show(|ui: &mut Ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 2. - BUTTON_SIZE.y * 2.);
+            if state.ticket.is_some() {
+                let rules = Rules::default();
+                ui.label(format!(
+                    "Looking for players: {}/{}",
+                    state.players, rules.players
+                ));
+            } else {
+                ui.label("Entering the queue...");
+            }
+            ui.add_space(MARGIN);
+            if ui
+                .add(Button::new("Cancel").min_size(BUTTON_SIZE))
+                .clicked()
+            {
+                state.active = false;
+                if let Some(ticket) = state.ticket.take() {
+                    commands.spawn(LeaveQueueRequest(ticket));
+                }
+                next_state.0 = Some(CurrentScreen::MainMenu);
+            }
+        });
+    });
+}
+
+fn request_status(mut commands: Commands, state: Res<QueueState>) {
+    if let Some(ticket) = &state.ticket {
+        commands.spawn(QueueStatusRequest(ticket.clone()));
+    }
+}
+
+fn on_queue_response(
+    mut commands: Commands,
+    mut events: EventReader<OnResponse<EnterQueueRequest>>,
+    mut state: ResMut<QueueState>,
+    config: Res<Configuration>,
+    mut next_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if let Some(OnResponse(status)) = events.iter().last() {
+        update(status, &mut commands, &mut state, &config, &mut next_state);
+    }
+}
+
+fn on_status_response(
+    mut commands: Commands,
+    mut events: EventReader<OnResponse<QueueStatusRequest>>,
+    mut state: ResMut<QueueState>,
+    config: Res<Configuration>,
+    mut next_state: ResMut<NextState<CurrentScreen>>,
+) {
+    if let Some(OnResponse(status)) = events.iter().last() {
+        update(status, &mut commands, &mut state, &config, &mut next_state);
+    }
+}
+
+/// Keeps waiting or enters the lobby of the created game.
+fn update(
+    status: &QueueStatus,
+    commands: &mut Commands,
+    state: &mut QueueState,
+    config: &Configuration,
+    next_state: &mut NextState<CurrentScreen>,
+) {
+    // Player may have cancelled while request was in flight.
+    if !state.active {
+        if let QueueStatus::Waiting { ticket, .. } = status {
+            commands.spawn(LeaveQueueRequest(ticket.clone()));
+        }
+        return;
+    }
+    match status {
+        QueueStatus::Waiting { ticket, players } => {
+            state.ticket = Some(ticket.clone());
+            state.players = *players;
+        }
+        QueueStatus::Matched(JoinGameResponse::Ok {
+            game_id,
+            player_id,
+            token,
+        }) => {
+            *state = QueueState::default();
+            commands.insert_resource(Session {
+                name: config.name.clone(),
+                id: *player_id,
+                game: *game_id,
+                token: token.clone(),
+                password: Some(String::new()),
+            });
+            // Game is already started, lobby switches to it on the first status.
+            next_state.0 = Some(CurrentScreen::Lobby);
+        }
+    }
+}
+
+fn on_queue_error(
+    mut events: EventReader<OnError<EnterQueueRequest>>,
+    mut status_errors: EventReader<OnError<QueueStatusRequest>>,
+    mut state: ResMut<QueueState>,
+    mut notifications: ResMut<Notifications>,
+    mut next_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let enter_error = events.iter().last().map(|OnError { error, .. }| error);
+    let status_error = status_errors
+        .iter()
+        .last()
+        .map(|OnError { error, .. }| error);
+    if let Some(error) = enter_error.or(status_error) {
+        if !state.active {
+            return;
+        }
+        notifications.push(format!("Quick play failed: {}", error.message));
+        *state = QueueState::default();
+        next_state.0 = Some(CurrentScreen::MainMenu);
+    }
+}
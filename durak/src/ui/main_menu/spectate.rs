@@ -0,0 +1,165 @@
+//! Screen shown while the spectator waits for the game to start.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_egui::egui::{Button, Ui};
+use durak_lib::{
+    network::SpectateResponse,
+    status::{lobby::LobbyStatus, SpectatorStatus},
+};
+
+use crate::{
+    network::{OnError, OnResponse, SpectateRequest, SpectatorStatusRequest},
+    session::{Session, SpectatorSession},
+    ui::{
+        notification::Notifications,
+        utils::{BUTTON_SIZE, MARGIN},
+        UiContext,
+    },
+    GameScreen, GameStarted,
+};
+
+use super::CurrentScreen;
+
+pub struct SpectateScreen;
+
+impl Plugin for SpectateScreen {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                display,
+                request_status.run_if(
+                    resource_exists::<SpectatorSession>()
+                        .and_then(on_timer(Duration::from_secs_f32(0.5))),
+                ),
+                on_status_response,
+                on_status_error,
+            )
+                .run_if(in_state(CurrentScreen::Spectate)),
+        )
+        .add_systems(Update, (on_spectate_response, on_spectate_error))
+        .add_systems(OnEnter(GameScreen::MainMenu), end_session);
+    }
+}
+
+/// Lobby of the watched game.
+#[derive(Resource, Debug)]
+struct WatchedLobby(LobbyStatus);
+
+fn display(
+    mut ctx: UiContext,
+    mut commands: Commands,
+    lobby: Option<Res<WatchedLobby>>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    ctx.margin(MARGIN).show(|ui: &mut Ui| {
+        ui.vertical_centered_justified(|ui| {
+            ui.add_space(ui.available_height() / 3.);
+            match lobby {
+                Some(lobby) => {
+                    ui.label("Waiting for the game to start...");
+                    for player in lobby.0.players.iter() {
+                        ui.label(player.name.as_str());
+                    }
+                }
+                None => {
+                    ui.label("Waiting for server...");
+                }
+            }
+            ui.add_space(ui.available_height() - BUTTON_SIZE.y);
+            ui.horizontal(|ui| {
+                if ui.add(Button::new("Leave").min_size(BUTTON_SIZE)).clicked() {
+                    commands.remove_resource::<SpectatorSession>();
+                    commands.remove_resource::<WatchedLobby>();
+                    menu_state.0 = Some(CurrentScreen::MainMenu);
+                }
+            });
+        });
+    });
+}
+
+fn request_status(mut commands: Commands, session: Res<SpectatorSession>) {
+    commands.spawn(SpectatorStatusRequest(session.into_header()));
+}
+
+fn on_spectate_response(
+    mut commands: Commands,
+    mut events: EventReader<OnResponse<SpectateRequest>>,
+) {
+    if let Some(OnResponse(SpectateResponse::Ok { game_id, token })) = events.iter().next() {
+        commands.remove_resource::<Session>();
+        commands.insert_resource(SpectatorSession {
+            game: *game_id,
+            token: token.clone(),
+        });
+    }
+}
+
+/// Returns to join screen, so spectator may fix entered data.
+fn on_spectate_error(
+    mut events: EventReader<OnError<SpectateRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let Some(OnError { error, .. }) = events.iter().last() else {
+        return;
+    };
+    notifications.push(format!("Can't watch the game: {}", error.message));
+    menu_state.0 = Some(CurrentScreen::JoinGame);
+}
+
+fn on_status_response(
+    mut commands: Commands,
+    mut responses: EventReader<OnResponse<SpectatorStatusRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+    mut state: ResMut<NextState<GameScreen>>,
+    mut started: EventWriter<GameStarted>,
+) {
+    let Some(OnResponse(status)) = responses.iter().last() else {
+        return;
+    };
+
+    match status {
+        SpectatorStatus::Lobby(lobby) => {
+            commands.insert_resource(WatchedLobby(lobby.clone()));
+        }
+        SpectatorStatus::Round(round) => {
+            started.send(GameStarted {
+                trump: round.trump,
+                opponents: round.players.clone(),
+            });
+            state.0 = Some(GameScreen::Round);
+            commands.remove_resource::<WatchedLobby>();
+        }
+        SpectatorStatus::Finished(status) => {
+            notifications.push(format!("Game is over, {} won", status.winner_name()));
+            menu_state.0 = Some(CurrentScreen::MainMenu);
+            commands.remove_resource::<WatchedLobby>();
+            commands.remove_resource::<SpectatorSession>();
+        }
+    }
+}
+
+/// Leaves the game if it is no longer accessible.
+fn on_status_error(
+    mut commands: Commands,
+    mut errors: EventReader<OnError<SpectatorStatusRequest>>,
+    mut notifications: ResMut<Notifications>,
+    mut menu_state: ResMut<NextState<CurrentScreen>>,
+) {
+    let Some(OnError { error, .. }) = errors.iter().last() else {
+        return;
+    };
+    notifications.push(format!("Lost access to the game: {}", error.message));
+    menu_state.0 = Some(CurrentScreen::MainMenu);
+    commands.remove_resource::<WatchedLobby>();
+    commands.remove_resource::<SpectatorSession>();
+}
+
+/// Spectator leaves the game once the round screen is closed.
+fn end_session(mut commands: Commands) {
+    commands.remove_resource::<SpectatorSession>();
+}
@@ -0,0 +1,106 @@
+//! Short-lived messages that are shown on top of any screen.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{Align2, Area, Frame, Order},
+    EguiContexts,
+};
+
+use crate::{
+    network::{
+        CreateGameRequest, JoinGameRequest, KickRequest, LeaveGameRequest, MyRequest, OnError,
+        PlayCardRequest, PostMessageRequest, ReadyRequest, RetreatRequest, StartGameRequest,
+        SwapSeatsRequest, TakeRequest,
+    },
+    ui::utils::MARGIN,
+};
+
+pub struct NotificationPlugin;
+
+impl Plugin for NotificationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Notifications>().add_systems(
+            Update,
+            (
+                (
+                    notify_on_error::<CreateGameRequest>,
+                    notify_on_error::<JoinGameRequest>,
+                    notify_on_error::<LeaveGameRequest>,
+                    notify_on_error::<ReadyRequest>,
+                    notify_on_error::<StartGameRequest>,
+                    notify_on_error::<KickRequest>,
+                    notify_on_error::<SwapSeatsRequest>,
+                    notify_on_error::<PlayCardRequest>,
+                    notify_on_error::<TakeRequest>,
+                    notify_on_error::<RetreatRequest>,
+                    notify_on_error::<PostMessageRequest>,
+                ),
+                display,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Messages that are currently shown to the player.
+#[derive(Resource, Debug, Default)]
+pub struct Notifications(Vec<Notification>);
+
+impl Notifications {
+    /// Time during which each message is shown.
+    const LIFETIME: Duration = Duration::from_secs(4);
+
+    /// Shows message to the player.
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.0.push(Notification {
+            message: message.into(),
+            timer: Timer::new(Self::LIFETIME, TimerMode::Once),
+        });
+    }
+}
+
+#[derive(Debug)]
+struct Notification {
+    message: String,
+    timer: Timer,
+}
+
+/// Shows reason of every rejected request `R`.
+fn notify_on_error<R: MyRequest + Component>(
+    mut errors: EventReader<OnError<R>>,
+    mut notifications: ResMut<Notifications>,
+) {
+    for OnError { error, .. } in errors.iter() {
+        notifications.push(capitalize(&error.message));
+    }
+}
+
+fn display(mut contexts: EguiContexts, time: Res<Time>, mut notifications: ResMut<Notifications>) {
+    notifications
+        .0
+        .retain_mut(|notification| !notification.timer.tick(time.delta()).finished());
+    if notifications.0.is_empty() {
+        return;
+    }
+
+    Area::new("notifications")
+        .order(Order::Foreground)
+        .anchor(Align2::CENTER_TOP, [0., MARGIN])
+        .show(contexts.ctx_mut(), |ui| {
+            for notification in notifications.0.iter() {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(&notification.message);
+                });
+            }
+        });
+}
+
+fn capitalize(message: &str) -> String {
+    let mut chars = message.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
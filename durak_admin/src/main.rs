@@ -0,0 +1,171 @@
+//! Command-line tool that calls admin endpoints of the server.
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use durak_lib::{
+    admin::{FinishQuery, GameQuery, GameSummary, PlayerQuery},
+    errors::ErrorResponse,
+    identifiers::{GameId, PlayerId},
+};
+use reqwest::{
+    blocking::{Client, RequestBuilder},
+    Method,
+};
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Admin tool for the Durak server")]
+struct Args {
+    /// Base URL of the server.
+    #[arg(long, env = "DURAK_SERVER_URL", default_value = concat!("http://", env!("DURAK_SERVER_ADDRESS")))]
+    server: String,
+    /// Admin key configured on the server.
+    #[arg(long, env = "DURAK_ADMIN_KEY", hide_env_values = true)]
+    key: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List all games.
+    List,
+    /// Print full state of the game.
+    Inspect {
+        #[arg(value_parser = parse_game_id)]
+        game: GameId,
+    },
+    /// Finish ongoing round.
+    Finish {
+        #[arg(value_parser = parse_game_id)]
+        game: GameId,
+        /// Player declared a winner. Player with the least cards wins by default.
+        #[arg(long, value_parser = parse_player_id)]
+        winner: Option<PlayerId>,
+    },
+    /// Remove player from the lobby.
+    Kick {
+        #[arg(value_parser = parse_game_id)]
+        game: GameId,
+        #[arg(value_parser = parse_player_id)]
+        player: PlayerId,
+    },
+    /// Delete the game.
+    Delete {
+        #[arg(value_parser = parse_game_id)]
+        game: GameId,
+    },
+}
+
+fn parse_game_id(s: &str) -> Result<GameId, String> {
+    s.parse()
+        .map_err(|_| format!("`{s}` is not a valid game id"))
+}
+
+fn parse_player_id(s: &str) -> Result<PlayerId, String> {
+    s.parse()
+        .map(PlayerId::new)
+        .map_err(|_| format!("`{s}` is not a valid player id"))
+}
+
+/// Client of admin endpoints.
+struct AdminClient {
+    client: Client,
+    server: String,
+    key: String,
+}
+
+impl AdminClient {
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{path}", self.server.trim_end_matches('/'));
+        self.client.request(method, url).bearer_auth(&self.key)
+    }
+
+    /// Sends request and decodes response body.
+    fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, String> {
+        let response = request.send().map_err(|err| err.to_string())?;
+        if response.status().is_success() {
+            response.json().map_err(|err| err.to_string())
+        } else {
+            let status = response.status();
+            match response.json::<ErrorResponse>() {
+                Ok(error) => Err(error.message),
+                Err(_) => Err(format!("server responded with {status}")),
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let client = AdminClient {
+        client: Client::new(),
+        server: args.server,
+        key: args.key,
+    };
+    match run(&client, args.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(client: &AdminClient, command: Command) -> Result<(), String> {
+    match command {
+        Command::List => {
+            let games: Vec<GameSummary> =
+                client.send(client.request(Method::GET, "/admin/games"))?;
+            println!("{:<10} {:<10} {:>8}  PLAYERS", "ID", "PHASE", "IDLE");
+            for game in games {
+                let players: Vec<_> = game
+                    .players
+                    .iter()
+                    .map(|player| format!("#{} {}", player.id, player.name))
+                    .collect();
+                println!(
+                    "{:<10} {:<10} {:>7}s  {}",
+                    game.id.to_string(),
+                    format!("{:?}", game.phase),
+                    game.idle_secs,
+                    players.join(", ")
+                );
+            }
+        }
+        Command::Inspect { game } => {
+            let state: serde_json::Value = client.send(
+                client
+                    .request(Method::GET, "/admin/game")
+                    .query(&GameQuery { id: game }),
+            )?;
+            println!("{}", serde_json::to_string_pretty(&state).unwrap());
+        }
+        Command::Finish { game, winner } => {
+            client.send::<()>(
+                client
+                    .request(Method::POST, "/admin/finish")
+                    .query(&FinishQuery { id: game, winner }),
+            )?;
+            println!("finished game `{game}`");
+        }
+        Command::Kick { game, player } => {
+            client.send::<()>(
+                client
+                    .request(Method::POST, "/admin/kick")
+                    .query(&PlayerQuery { id: game, player }),
+            )?;
+            println!("kicked player #{player} from game `{game}`");
+        }
+        Command::Delete { game } => {
+            client.send::<()>(
+                client
+                    .request(Method::POST, "/admin/delete")
+                    .query(&GameQuery { id: game }),
+            )?;
+            println!("deleted game `{game}`");
+        }
+    }
+    Ok(())
+}
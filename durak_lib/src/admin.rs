@@ -0,0 +1,59 @@
+//! Types used by admin endpoints of the server.
+//!
+//! Admin endpoints are authorized with a separate admin key instead of player tokens.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    identifiers::{GameId, PlayerId},
+    status::PlayerData,
+};
+
+/// Short description of the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct GameSummary {
+    pub id: GameId,
+    pub phase: PhaseKind,
+    pub players: Vec<PlayerData>,
+    /// Number of seconds since the last request to the game.
+    pub idle_secs: u64,
+}
+
+/// Phase of the game without its state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum PhaseKind {
+    Lobby,
+    Round,
+    Finished,
+}
+
+/// Query that selects a game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct GameQuery {
+    pub id: GameId,
+}
+
+/// Query that selects a player of the game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct PlayerQuery {
+    pub id: GameId,
+    pub player: PlayerId,
+}
+
+/// Query that finishes the game.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct FinishQuery {
+    pub id: GameId,
+    /// Player that is declared a winner.
+    ///
+    /// If not provided, player with the least cards wins.
+    pub winner: Option<PlayerId>,
+}
@@ -0,0 +1,27 @@
+//! Text chat of the game.
+//!
+//! Messages are posted by players and delivered to everyone with the status of the game.
+
+use serde::{Deserialize, Serialize};
+
+use crate::identifiers::PlayerId;
+
+/// Message posted to the chat of the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ChatMessage {
+    /// Sequence number of the message inside the game.
+    pub id: u32,
+    /// Player that posted the message.
+    pub author: PlayerId,
+    /// Name of the player at the time of posting.
+    pub name: String,
+    pub text: String,
+}
+
+/// Request body used to post a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PostMessageData {
+    pub text: String,
+}
@@ -1,7 +1,120 @@
+#[cfg(feature = "axum")]
+use axum::{
+    http::header::RETRY_AFTER,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use http::StatusCode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-#[derive(Debug, Error, Serialize, Deserialize)]
+/// Error returned by any endpoint of the server.
+///
+/// It is sent to the client wrapped into [ErrorResponse].
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ApiError {
+    #[error("authorization failed: {0}")]
+    AuthFailed(#[from] AuthFailed),
+    #[error("{0}")]
+    GameNotFound(#[from] GameNotFound),
+    #[error("{0}")]
+    InvalidPhase(#[from] InvalidPhase),
+    #[error("{0}")]
+    PlayCard(#[from] PlayCardError),
+    #[error("{0}")]
+    Action(#[from] ActionError),
+    #[error("password doesn't match")]
+    InvalidPassword,
+    #[error("game is already full")]
+    TooManyPlayers,
+    #[error("server can't host more games right now")]
+    TooManyGames,
+    #[error("player is not in the matchmaking queue")]
+    NotQueued,
+    #[error("{0}")]
+    RateLimited(#[from] RateLimited),
+    #[error("malformed request: {0}")]
+    BadRequest(String),
+    #[error("request body is too large")]
+    PayloadTooLarge,
+    #[error("requested endpoint doesn't exist")]
+    NotFound,
+}
+
+impl ApiError {
+    /// Returns HTTP status code of the response carrying this error.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::AuthFailed(AuthFailed::NotHost | AuthFailed::Spectator) => {
+                StatusCode::FORBIDDEN
+            }
+            ApiError::AuthFailed(_) => StatusCode::UNAUTHORIZED,
+            ApiError::GameNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::InvalidPhase(_) => StatusCode::BAD_REQUEST,
+            ApiError::PlayCard(_) => StatusCode::BAD_REQUEST,
+            ApiError::Action(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidPassword => StatusCode::BAD_REQUEST,
+            ApiError::TooManyPlayers => StatusCode::BAD_REQUEST,
+            ApiError::TooManyGames => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::NotQueued => StatusCode::NOT_FOUND,
+            ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+impl From<AccessError> for ApiError {
+    fn from(value: AccessError) -> Self {
+        match value {
+            AccessError::AuthFailed(err) => err.into(),
+            AccessError::GameNotFound(err) => err.into(),
+            AccessError::InvalidPhase(err) => err.into(),
+        }
+    }
+}
+
+/// Body of every unsuccessful response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ErrorResponse {
+    /// Reason of the failure.
+    pub error: ApiError,
+    /// Human-readable description of the failure.
+    pub message: String,
+}
+
+impl From<ApiError> for ErrorResponse {
+    fn from(error: ApiError) -> Self {
+        Self {
+            message: error.to_string(),
+            error,
+        }
+    }
+}
+
+/// Error is also inserted into response extensions, so middlewares may inspect it.
+#[cfg(feature = "axum")]
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let code = self.status_code();
+        let mut response = match self {
+            ApiError::RateLimited(RateLimited { retry_after }) => {
+                let retry_after = [(RETRY_AFTER, retry_after.to_string())];
+                (code, retry_after, Json(ErrorResponse::from(self.clone()))).into_response()
+            }
+            _ => (code, Json(ErrorResponse::from(self.clone()))).into_response(),
+        };
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[error("failed to play card")]
 pub enum PlayCardError {
     #[error("too much attacking cards: only six cards can attack at time")]
@@ -14,8 +127,24 @@ pub enum PlayCardError {
     NotInHand,
 }
 
+/// Failed to perform game action other than playing a card.
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum ActionError {
+    #[error("not enough players to start the game")]
+    NotEnoughPlayers,
+    #[error("not every player is ready to start the game")]
+    PlayersNotReady,
+    #[error("it is turn of another player")]
+    InvalidTurn,
+    #[error("cards can't be taken right now")]
+    CantTake,
+    #[error("attack can't be stopped right now")]
+    CantRetreat,
+}
+
 /// Failed to access some data.
-#[derive(Debug, Error, Serialize, Deserialize)]
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
 pub enum AccessError {
     #[error("authorization failed: {0}")]
     AuthFailed(#[from] AuthFailed),
@@ -25,19 +154,9 @@ pub enum AccessError {
     InvalidPhase(#[from] InvalidPhase),
 }
 
-impl AccessError {
-    pub fn status_code(&self) -> http::StatusCode {
-        use http::StatusCode;
-        match self {
-            AccessError::AuthFailed(_) => StatusCode::UNAUTHORIZED,
-            AccessError::GameNotFound(_) => StatusCode::NOT_FOUND,
-            AccessError::InvalidPhase(_) => StatusCode::BAD_REQUEST,
-        }
-    }
-}
-
 /// Token authorization failed
-#[derive(Debug, Error, Serialize, Deserialize)]
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum AuthFailed {
     #[error("request didn't contain Authorization header")]
     NoAuthHeader,
@@ -45,14 +164,63 @@ pub enum AuthFailed {
     InvalidAuthHeader,
     #[error("provided token doesn't match")]
     TokenDoesntMatch,
+    #[error("provided token is expired")]
+    TokenExpired,
+    #[error("only host of the game can do that")]
+    NotHost,
+    #[error("spectators can't do that")]
+    Spectator,
+    #[error("player was removed from the game")]
+    Kicked,
+    #[error("player is not in the game")]
+    NotAPlayer,
 }
 
 /// Game was not found.
-#[derive(Debug, Error, Serialize, Deserialize)]
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[error("game with provided id wasn't found")]
 pub struct GameNotFound;
 
 /// Invalid game phase.
-#[derive(Debug, Error, Serialize, Deserialize)]
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[error("requested endpoint is not accessible during current phase")]
 pub struct InvalidPhase;
+
+/// Too many requests were sent in a short period of time.
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[error("too many requests, retry in {retry_after} seconds")]
+pub struct RateLimited {
+    /// Number of seconds after which request may be retried.
+    pub retry_after: u64,
+}
+
+/// Text is not a valid invite link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum InvalidInviteLink {
+    #[error("not an invite link")]
+    NotInvite,
+    #[error("invite link contains invalid join code")]
+    GameId,
+    #[error("invite link is malformed")]
+    Encoding,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApiError, ErrorResponse, PlayCardError};
+
+    #[test]
+    fn test_error_response_roundtrip() {
+        let response = ErrorResponse::from(ApiError::PlayCard(PlayCardError::NotInHand));
+        let json = serde_json::to_string(&response).unwrap();
+        let decoded: ErrorResponse = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            decoded.error,
+            ApiError::PlayCard(PlayCardError::NotInHand)
+        ));
+        assert_eq!(decoded.message, "that card is not in hand");
+    }
+}
@@ -1,7 +1,8 @@
-//! Data structures that store game data and implement game logic.pub mod card;
+//! Data structures that store game data and implement game logic.
 
 pub mod card;
 pub mod deck;
 pub mod hand;
 pub mod player;
+pub mod rules;
 pub mod table;
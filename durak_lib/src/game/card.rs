@@ -6,6 +6,8 @@ use strum::EnumIter;
 ///
 /// Each deck must have exactly one card of each type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema, utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Component))]
 pub struct Card {
     pub suit: CardSuit,
@@ -17,15 +19,14 @@ impl Card {
     pub fn can_beat(&self, other: Card, trump: CardSuit) -> bool {
         if self.suit == other.suit {
             self.rank > other.rank
-        } else if self.suit == trump {
-            true
         } else {
-            false
+            self.suit == trump
         }
     }
 }
 
 #[derive(EnumIter, Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CardSuit {
     Clover,
@@ -37,6 +38,7 @@ pub enum CardSuit {
 #[derive(
     EnumIter, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
 )]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CardRank {
     Six,
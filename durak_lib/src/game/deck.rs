@@ -1,8 +1,7 @@
 //! Deck of cards used in game.
 
-#![allow(clippy::new_without_default)]
-
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
 use super::card::{Card, CardRank, CardSuit};
@@ -10,7 +9,7 @@ use super::card::{Card, CardRank, CardSuit};
 /// Deck of cards that is used as main source of cards throughout the game.
 ///
 /// Deck in standard version of the game consists of 36 unique cards.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Deck(Vec<Card>);
 
 impl Deck {
@@ -27,7 +26,12 @@ impl Deck {
 
     /// Shuffles all cards in the deck.
     pub fn shuffle(&mut self) {
-        self.0.shuffle(&mut thread_rng());
+        self.shuffle_with(&mut thread_rng());
+    }
+
+    /// Shuffles all cards in the deck using provided random number generator.
+    pub fn shuffle_with(&mut self, rng: &mut impl Rng) {
+        self.0.shuffle(rng);
     }
 
     /// Takes card from the top of the deck.
@@ -50,3 +54,9 @@ impl Deck {
         self.0.len()
     }
 }
+
+impl Default for Deck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -6,6 +6,7 @@ use super::card::Card;
 
 /// Hand is a place where all cards owned by player are kept.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Component))]
 pub struct Hand(Vec<Card>);
 
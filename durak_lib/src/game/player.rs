@@ -18,6 +18,7 @@ pub struct Player {
 
 /// Limited information about specific player.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Component))]
 pub struct Opponent {
     pub id: PlayerId,
@@ -0,0 +1,44 @@
+//! Rules that may differ between games.
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Rules of the game, chosen by the host when the game is created.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Rules {
+    /// Number of players needed to start the game.
+    pub players: u8,
+    /// Seconds the player on turn has to act, turns are not limited if `None`.
+    ///
+    /// Once time is over, the server acts on behalf of that player,
+    /// even if nobody is connected to the game.
+    #[serde(default)]
+    pub turn_time: Option<u16>,
+}
+
+impl Rules {
+    /// Maximum number of players in a game, only two-player games are supported so far.
+    pub const MAX_PLAYERS: u8 = 2;
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            players: 2,
+            turn_time: None,
+        }
+    }
+}
+
+/// Short human-readable summary of the rules.
+impl Display for Rules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} players", self.players)?;
+        if let Some(turn_time) = self.turn_time {
+            write!(f, ", {turn_time} s per turn")?;
+        }
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use super::card::{Card, CardSuit};
 
 /// Table is the main location where the game is played.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Component))]
 pub struct Table(Vec<(Card, Option<Card>)>);
 
@@ -69,15 +70,7 @@ impl Table {
     /// Returns `None` if taking cards is against the rules.
     pub fn take(&mut self) -> Option<Vec<Card>> {
         if self.can_take() {
-            let cards = std::mem::replace(&mut self.0, Vec::with_capacity(6));
-            let mut result = Vec::with_capacity(cards.len() * 2);
-            for (attacking, defending) in cards {
-                result.push(attacking);
-                if let Some(defending) = defending {
-                    result.push(defending);
-                }
-            }
-            Some(result)
+            Some(self.clear())
         } else {
             None
         }
@@ -85,14 +78,27 @@ impl Table {
 
     /// Removes all cards from the table.
     ///
-    /// Returns `true` if retreat was successful.
-    pub fn retreat(&mut self) -> bool {
+    /// Returns cards that were beaten off.
+    /// Returns `None` if retreat is against the rules.
+    pub fn retreat(&mut self) -> Option<Vec<Card>> {
         if self.can_retreat() {
-            self.0.clear();
-            true
+            Some(self.clear())
         } else {
-            false
+            None
+        }
+    }
+
+    /// Removes all cards from the table and returns them.
+    fn clear(&mut self) -> Vec<Card> {
+        let cards = std::mem::replace(&mut self.0, Vec::with_capacity(6));
+        let mut result = Vec::with_capacity(cards.len() * 2);
+        for (attacking, defending) in cards {
+            result.push(attacking);
+            if let Some(defending) = defending {
+                result.push(defending);
+            }
         }
+        result
     }
 
     /// Returns `true` if all attacks on the table are answered.
@@ -2,47 +2,81 @@
 
 use std::{fmt::Display, str::FromStr};
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// A unique identificator of the game.
+///
+/// It is displayed as a short join code made of [`GameId::CODE_LENGTH`] characters of
+/// Crockford's base32, which doesn't contain easily confused letters.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(transparent)]
 pub struct GameId(pub u32);
 
 impl GameId {
+    /// Number of characters in the join code.
+    pub const CODE_LENGTH: usize = 6;
+    /// Characters used in join codes.
+    const ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    /// Number of distinct join codes.
+    const CODES: u32 = 1 << (5 * Self::CODE_LENGTH);
+
     pub fn new(id: u32) -> Self {
         GameId(id)
     }
+
+    /// Generates random id that fits into the join code.
+    pub fn random(rng: &mut impl Rng) -> Self {
+        GameId(rng.gen_range(0..Self::CODES))
+    }
+
+    /// Returns value of a join code character, ignoring case and confusable letters.
+    fn decode_char(char: char) -> Option<u32> {
+        let char = match char.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            char => char,
+        };
+        let position = Self::ALPHABET.iter().position(|&c| c as char == char)?;
+        Some(position as u32)
+    }
 }
 
 impl FromStr for GameId {
     type Err = ();
 
+    /// Parses the join code. Case, spaces and dashes are ignored.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.is_empty() {
-            return Err(());
-        }
-        let mut result = 0;
-        for (index, char) in s.chars().rev().enumerate() {
-            let Some(digit) = char.to_digit(16) else {
-                return Err(());
-            };
-            let base_multiplier = 16u32.checked_pow(index as u32).ok_or(())?;
-            let addition = (digit as u32).checked_mul(base_multiplier).ok_or(())?;
-            result += addition;
-        }
-        Ok(GameId(result))
+        let mut chars = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .peekable();
+        chars.peek().ok_or(())?;
+        chars.try_fold(GameId(0), |GameId(id), char| {
+            let digit = Self::decode_char(char).ok_or(())?;
+            let id = id.checked_mul(32).ok_or(())?.checked_add(digit).ok_or(())?;
+            Ok(GameId(id))
+        })
     }
 }
 
 impl Display for GameId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:X}", self.0)
+        let mut code = Vec::with_capacity(Self::CODE_LENGTH);
+        let mut value = self.0;
+        while value != 0 || code.len() < Self::CODE_LENGTH {
+            code.push(Self::ALPHABET[(value % 32) as usize]);
+            value /= 32;
+        }
+        code.reverse();
+        f.write_str(std::str::from_utf8(&code).unwrap())
     }
 }
 
 /// Unique identifier of the player within the game.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(transparent)]
 pub struct PlayerId(u8);
 
@@ -51,11 +85,6 @@ impl PlayerId {
     pub fn new(id: u8) -> Self {
         PlayerId(id)
     }
-
-    /// Returns `true` if that player is host.
-    pub fn is_host(&self) -> bool {
-        self.0 == 0
-    }
 }
 
 impl Display for PlayerId {
@@ -77,4 +106,18 @@ mod test {
         let parsed = GameId::from_str(&s).unwrap();
         assert_eq!(game_id, parsed);
     }
+
+    #[test]
+    fn test_game_id_codes() {
+        let game_id = GameId::from_str("7K3-QX9").unwrap();
+        assert_eq!(game_id.to_string(), "7K3QX9");
+        assert_eq!(GameId::from_str("7k3qx9"), Ok(game_id));
+        assert_eq!(GameId::from_str("o1L"), GameId::from_str("011"));
+        assert_eq!(GameId::new(1).to_string(), "000001");
+        assert!(GameId::from_str("").is_err());
+        assert!(GameId::from_str("U").is_err());
+        assert!(GameId::from_str("ZZZZZZZZ").is_err());
+        let largest = GameId::new(u32::MAX);
+        assert_eq!(GameId::from_str(&largest.to_string()), Ok(largest));
+    }
 }
@@ -0,0 +1,143 @@
+//! Links that invite other players into the game.
+//!
+//! Link looks like `durak://join/7K3QX9?pw=secret&server=example.com%3A3000`.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{errors::InvalidInviteLink, identifiers::GameId};
+
+/// Beginning of every invite link.
+pub const INVITE_PREFIX: &str = "durak://join/";
+
+/// Everything needed to join the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InviteLink {
+    pub id: GameId,
+    pub password: String,
+    /// Address of the server that hosts the game.
+    pub server: Option<String>,
+}
+
+impl FromStr for InviteLink {
+    type Err = InvalidInviteLink;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let rest = s
+            .get(..INVITE_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(INVITE_PREFIX))
+            .map(|_| &s[INVITE_PREFIX.len()..])
+            .ok_or(InvalidInviteLink::NotInvite)?;
+        let (id, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let id = id
+            .trim_end_matches('/')
+            .parse()
+            .map_err(|_| InvalidInviteLink::GameId)?;
+
+        let mut invite = InviteLink {
+            id,
+            password: String::new(),
+            server: None,
+        };
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(value).ok_or(InvalidInviteLink::Encoding)?;
+            match key {
+                "pw" => invite.password = value,
+                "server" if !value.is_empty() => invite.server = Some(value),
+                // Unknown parameters may be added by newer versions.
+                _ => {}
+            }
+        }
+        Ok(invite)
+    }
+}
+
+impl Display for InviteLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{INVITE_PREFIX}{}?pw={}",
+            self.id,
+            encode(&self.password)
+        )?;
+        if let Some(server) = &self.server {
+            write!(f, "&server={}", encode(server))?;
+        }
+        Ok(())
+    }
+}
+
+/// Percent-encodes everything except unreserved characters.
+fn encode(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    result
+}
+
+/// Decodes percent-encoded string, treating `+` as a space.
+fn decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            b'+' => bytes.push(b' '),
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{errors::InvalidInviteLink, identifiers::GameId, invite::InviteLink};
+
+    #[test]
+    fn test_invite_link_roundtrip() {
+        let invite = InviteLink {
+            id: GameId::new(1234),
+            password: String::from("pass word&=%ы"),
+            server: Some(String::from("example.com:3000")),
+        };
+        let link = invite.to_string();
+        assert_eq!(
+            link,
+            "durak://join/00016J?pw=pass%20word%26%3D%25%D1%8B&server=example.com%3A3000"
+        );
+        assert_eq!(link.parse(), Ok(invite));
+    }
+
+    #[test]
+    fn test_invite_link_parsing() {
+        let invite: InviteLink = " DURAK://join/16j?server=localhost:3000&extra \n"
+            .parse()
+            .unwrap();
+        assert_eq!(invite.id, GameId::new(1234));
+        assert_eq!(invite.password, "");
+        assert_eq!(invite.server.as_deref(), Some("localhost:3000"));
+
+        assert_eq!(
+            "16J".parse::<InviteLink>(),
+            Err(InvalidInviteLink::NotInvite)
+        );
+        assert_eq!(
+            "durak://join/?pw=1".parse::<InviteLink>(),
+            Err(InvalidInviteLink::GameId)
+        );
+        assert_eq!(
+            "durak://join/16J?pw=%F".parse::<InviteLink>(),
+            Err(InvalidInviteLink::Encoding)
+        );
+    }
+}
@@ -1,5 +1,9 @@
+pub mod admin;
+pub mod chat;
 pub mod errors;
 pub mod game;
 pub mod identifiers;
+pub mod invite;
+pub mod matchmaking;
 pub mod network;
 pub mod status;
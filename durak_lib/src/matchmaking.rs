@@ -0,0 +1,72 @@
+//! Types used by the matchmaking queue.
+//!
+//! Queued players are grouped by the rules they want to play, and a game is created
+//! as soon as enough players are waiting.
+
+#[cfg(feature = "axum")]
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{game::rules::Rules, network::JoinGameResponse};
+
+/// Request body used to enter the queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QueueData {
+    pub name: String,
+    /// Rules of the game player wants to play, including number of players.
+    #[serde(default)]
+    pub rules: Rules,
+}
+
+/// Opaque identifier of the queued player.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(transparent)]
+pub struct Ticket(String);
+
+impl Ticket {
+    pub fn new(ticket: String) -> Self {
+        Self(ticket)
+    }
+
+    /// Returns string representation of the ticket.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Query that selects queued player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct TicketQuery {
+    pub ticket: Ticket,
+}
+
+/// State of the queued player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum QueueStatus {
+    /// Player waits for others.
+    ///
+    /// Status should be polled with the ticket, otherwise player leaves the queue.
+    Waiting {
+        ticket: Ticket,
+        /// Number of players waiting for the same game, including this one.
+        players: u8,
+    },
+    /// Game is created and started, player joined it with provided credentials.
+    Matched(JoinGameResponse),
+}
+
+#[cfg(feature = "axum")]
+impl IntoResponse for QueueStatus {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
+}
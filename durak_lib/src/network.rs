@@ -12,48 +12,101 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    errors::AccessError,
-    game::card::Card,
+    errors::AuthFailed,
+    game::{card::Card, rules::Rules},
     identifiers::{GameId, PlayerId},
 };
 
+/// Version of the protocol spoken by server and client.
+///
+/// Must be incremented on every incompatible change of requests or responses.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Response to the version request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct VersionResponse {
+    /// Version of the protocol spoken by the server.
+    pub protocol: u32,
+    /// Version of the server itself.
+    pub server: String,
+}
+
+impl VersionResponse {
+    /// Returns `true` if server speaks the same protocol as this build.
+    pub fn is_compatible(&self) -> bool {
+        self.protocol == PROTOCOL_VERSION
+    }
+}
+
+#[cfg(feature = "axum")]
+impl IntoResponse for VersionResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
 /// Token used to uniquely identify each player session.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+///
+/// Token is opaque to the client: it is signed by the server and carries all data
+/// required to authenticate the player.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(transparent)]
-pub struct Token(u64);
+pub struct Token(String);
 
 impl Token {
-    pub fn new(token: u64) -> Self {
+    pub fn new(token: String) -> Self {
         Self(token)
     }
+
+    /// Returns string representation of the token.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 /// Data that is passed by requests in `Authorization` header.
 ///
-/// Should be encoded as json.
+/// Should be encoded using `Bearer` scheme.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AuthHeader {
-    pub game_id: GameId,
-    pub player_id: PlayerId,
-    pub token: Token,
-}
+pub struct AuthHeader(pub Token);
 
 impl AuthHeader {
+    const SCHEME: &'static str = "Bearer ";
+
     pub fn into_header(&self) -> HeaderValue {
-        let s = serde_json::to_string(&self).unwrap();
+        let s = format!("{}{}", Self::SCHEME, self.0.as_str());
         HeaderValue::from_str(s.as_str()).unwrap()
     }
+
+    /// Parses value of `Authorization` header.
+    pub fn from_header(header: &HeaderValue) -> Result<Self, AuthFailed> {
+        let token = header
+            .to_str()
+            .ok()
+            .and_then(|header| header.strip_prefix(Self::SCHEME))
+            .ok_or(AuthFailed::InvalidAuthHeader)?;
+        Ok(Self(Token::new(token.to_owned())))
+    }
 }
 
-/// Query parameters used to create new game.
+/// Request body used to create new game.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateGameData {
     pub name: String,
     pub password: String,
+    #[serde(default)]
+    pub rules: Rules,
+    /// Whether the game is listed in the lobby browser.
+    #[serde(default)]
+    pub public: bool,
 }
 
 /// Response for [CreateGameData] request.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum CreateGameResponse {
     Ok {
         game_id: GameId,
@@ -72,8 +125,26 @@ impl IntoResponse for CreateGameResponse {
     }
 }
 
-/// Query parameters used to join created game.
+/// Query that selects player to remove from the lobby.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct KickQuery {
+    pub player: PlayerId,
+}
+
+/// Query that selects seats of the lobby to swap.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+#[cfg_attr(feature = "openapi", into_params(parameter_in = Query))]
+pub struct SwapSeatsQuery {
+    pub first: u8,
+    pub second: u8,
+}
+
+/// Request body used to join created game.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct JoinGameData {
     pub id: GameId,
     pub name: String,
@@ -81,7 +152,8 @@ pub struct JoinGameData {
 }
 
 /// Response for [JoinGameData] request.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum JoinGameResponse {
     /// Joined successfully.
     Ok {
@@ -89,58 +161,58 @@ pub enum JoinGameResponse {
         player_id: PlayerId,
         token: Token,
     },
-    /// Password doesn't match.
-    InvalidPassword,
-    /// Game is already full.
-    TooManyPlayers,
-    /// Failed to access game.
-    AccessError(AccessError),
-}
-
-impl From<AccessError> for JoinGameResponse {
-    fn from(value: AccessError) -> Self {
-        Self::AccessError(value)
-    }
 }
 
 #[cfg(feature = "axum")]
 impl IntoResponse for JoinGameResponse {
     fn into_response(self) -> Response {
-        let code = match &self {
+        let code = match self {
             JoinGameResponse::Ok { .. } => StatusCode::OK,
-            JoinGameResponse::InvalidPassword => StatusCode::BAD_REQUEST,
-            JoinGameResponse::TooManyPlayers => StatusCode::BAD_REQUEST,
-            JoinGameResponse::AccessError(ref error) => error.status_code(),
         };
         (code, Json(self)).into_response()
     }
 }
 
-/// Query parameters used to play card.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PlayCardData {
-    pub player: PlayerId,
-    pub card: Card,
+/// Request body used to watch the game.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpectateData {
+    pub id: GameId,
+    pub password: String,
 }
 
-/// Response to card played.
-#[derive(Debug, Serialize, Deserialize)]
-pub enum PlayCardResponse {
-    Ok,
-    InvalidCard,
-    NotInHand,
-    AuthFailed,
+/// Response for [SpectateData] request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum SpectateResponse {
+    /// Read-only token that only allows to request spectator status.
+    Ok { game_id: GameId, token: Token },
 }
 
 #[cfg(feature = "axum")]
-impl IntoResponse for PlayCardResponse {
+impl IntoResponse for SpectateResponse {
     fn into_response(self) -> Response {
-        let code = match &self {
-            PlayCardResponse::Ok => StatusCode::OK,
-            PlayCardResponse::InvalidCard => StatusCode::BAD_REQUEST,
-            PlayCardResponse::NotInHand => StatusCode::BAD_REQUEST,
-            PlayCardResponse::AuthFailed => StatusCode::UNAUTHORIZED,
-        };
-        (code, Json(self)).into_response()
+        (StatusCode::OK, Json(self)).into_response()
     }
 }
+
+/// Public lobby listed in the lobby browser.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LobbySummary {
+    pub id: GameId,
+    /// Name of the host.
+    pub host: String,
+    /// Number of joined players.
+    pub players: u8,
+    pub rules: Rules,
+    /// Whether password is needed to join the game.
+    pub has_password: bool,
+}
+
+/// Query parameters used to play card.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayCardData {
+    pub player: PlayerId,
+    pub card: Card,
+}
@@ -13,23 +13,23 @@ use axum::{response::IntoResponse, Json};
 use http::StatusCode;
 
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
 
-use crate::{
-    errors::{AuthFailed, GameNotFound},
-    identifiers::PlayerId,
-};
+use crate::identifiers::PlayerId;
 
-use self::{finished::FinishedStatus, lobby::LobbyStatus, round::RoundStatus};
+use self::{
+    finished::FinishedStatus,
+    lobby::LobbyStatus,
+    round::{RoundStatus, SpectatorRoundStatus},
+};
 
 /// Response to the status request.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Resource))]
 pub enum StatusResponse {
     Lobby(LobbyStatus),
     Round(RoundStatus),
     Finished(FinishedStatus),
-    Error(StatusRequestError),
 }
 
 #[cfg(feature = "axum")]
@@ -39,26 +39,32 @@ impl IntoResponse for StatusResponse {
             StatusResponse::Lobby(_) => StatusCode::OK,
             StatusResponse::Round(_) => StatusCode::OK,
             StatusResponse::Finished(_) => StatusCode::OK,
-            StatusResponse::Error(ref error) => match error {
-                StatusRequestError::GameNotFound(_) => StatusCode::NOT_FOUND,
-                StatusRequestError::AuthFailed(_) => StatusCode::UNAUTHORIZED,
-            },
         };
         (status_code, Json(self)).into_response()
     }
 }
 
-/// Error that occurred when trying to get status of the game.
-#[derive(Debug, Serialize, Deserialize, Error)]
-pub enum StatusRequestError {
-    #[error("{0}")]
-    GameNotFound(#[from] GameNotFound),
-    #[error("auth failed: {0}")]
-    AuthFailed(#[from] AuthFailed),
+/// Response to the spectator status request.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum SpectatorStatus {
+    Lobby(LobbyStatus),
+    Round(SpectatorRoundStatus),
+    Finished(FinishedStatus),
+}
+
+#[cfg(feature = "axum")]
+impl IntoResponse for SpectatorStatus {
+    fn into_response(self) -> axum::response::Response {
+        (StatusCode::OK, Json(self)).into_response()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PlayerData {
     pub id: PlayerId,
     pub name: String,
+    /// Seat at the table, players act in order of their seats.
+    pub seat: u8,
 }
@@ -5,9 +5,12 @@ use crate::identifiers::PlayerId;
 use super::PlayerData;
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FinishedStatus {
     pub players: Vec<PlayerData>,
     pub winner: PlayerId,
+    /// Player that ended the game by leaving it.
+    pub forfeited: Option<PlayerId>,
 }
 
 impl FinishedStatus {
@@ -18,4 +21,13 @@ impl FinishedStatus {
             .map(|data| data.name.as_str())
             .expect("invalid finished status report")
     }
+
+    /// Returns name of the player that left the game, if it ended that way.
+    pub fn forfeited_name(&self) -> Option<&str> {
+        let forfeited = self.forfeited?;
+        self.players
+            .iter()
+            .find(|data| data.id == forfeited)
+            .map(|data| data.name.as_str())
+    }
 }
@@ -1,19 +1,36 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{chat::ChatMessage, identifiers::PlayerId};
+
 use super::PlayerData;
 
 /// Status of the preparing game.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Resource))]
 pub struct LobbyStatus {
     /// List of joined players.
     pub players: Vec<PlayerData>,
+    /// Player that manages the lobby.
+    pub host: Option<PlayerId>,
+    /// Number of seats at the table.
+    pub seats: u8,
+    /// Players that are ready to start.
+    pub ready: Vec<PlayerId>,
+    /// Seconds left until the game may be started, `None` unless every seat is taken by ready player.
+    pub countdown: Option<u64>,
+    /// Latest messages of the chat.
+    pub chat: Vec<ChatMessage>,
 }
 
 impl LobbyStatus {
+    /// Returns `true` if the player is ready to start.
+    pub fn is_ready(&self, player: PlayerId) -> bool {
+        self.ready.contains(&player)
+    }
+
     /// Returns `true` if the game may be started by the host.
     pub fn can_start(&self) -> bool {
-        // TODO: allow more than two players.
-        self.players.len() == 2
+        self.countdown == Some(0)
     }
 }
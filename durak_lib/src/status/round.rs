@@ -1,17 +1,21 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    chat::ChatMessage,
     game::{card::Card, hand::Hand, player::Opponent, table::Table},
     identifiers::PlayerId,
 };
 
 /// Status of the ongoing game.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RoundStatus {
     /// Trump for the round.
     pub trump: Card,
     /// What player should take action now.
     pub turn: PlayerId,
+    /// Seconds left for the player on turn to act, `None` if turns are not limited.
+    pub turn_time_left: Option<u64>,
     /// Attacker of the round.
     pub attacker: PlayerId,
     /// Defender of the round.
@@ -26,4 +30,34 @@ pub struct RoundStatus {
     ///
     /// Doesn't include player that requested status report.
     pub opponents: Vec<Opponent>,
+    /// Latest messages of the chat.
+    pub chat: Vec<ChatMessage>,
+}
+
+/// Status of the ongoing game shown to spectators.
+///
+/// Unlike [RoundStatus], it doesn't contain any hands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpectatorRoundStatus {
+    /// Trump for the round.
+    pub trump: Card,
+    /// What player should take action now.
+    pub turn: PlayerId,
+    /// Seconds left for the player on turn to act, `None` if turns are not limited.
+    pub turn_time_left: Option<u64>,
+    /// Attacker of the round.
+    pub attacker: PlayerId,
+    /// Defender of the round.
+    pub defender: PlayerId,
+    /// Table state.
+    pub table: Table,
+    /// Number of cards left in deck.
+    pub deck_size: u8,
+    /// Cards that were beaten off and left the game.
+    pub discard: Vec<Card>,
+    /// All players with numbers of cards in their hands.
+    pub players: Vec<Opponent>,
+    /// Latest messages of the chat.
+    pub chat: Vec<ChatMessage>,
 }
@@ -0,0 +1,224 @@
+//! Measures throughput of the server under many concurrent games.
+//!
+//! Server binary is started on a free port, then fixed number of clients poll `/game/status`
+//! of started games as fast as possible. Run with `cargo bench -p durak_server`.
+
+use std::{
+    net::{SocketAddr, TcpListener},
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use durak_lib::{
+    game::rules::Rules,
+    network::{CreateGameData, CreateGameResponse, JoinGameData, JoinGameResponse, Token},
+    status::StatusResponse,
+};
+use hyper::{body, client::HttpConnector, header, Body, Client, Method, Request};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Numbers of concurrent games to measure.
+const GAME_COUNTS: [usize; 4] = [1, 16, 128, 1024];
+/// Number of clients sending requests at the same time.
+const CLIENTS: usize = 64;
+/// Duration of each measurement.
+const DURATION: Duration = Duration::from_secs(3);
+
+/// Server process that is killed on drop.
+struct Server {
+    process: Child,
+    address: SocketAddr,
+}
+
+impl Server {
+    fn start() -> Self {
+        let address = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("free port should be available");
+        let process = Command::new(env!("CARGO_BIN_EXE_durak_server"))
+            .args(["--address", &address.to_string()])
+            .args(["--log-level", "warn"])
+            .args(["--max-games", "100000"])
+            .args(["--rate-limit-ip", "1000000"])
+            .args(["--rate-limit-game", "1000000"])
+            // Games start as soon as both players are ready.
+            .args(["--start-countdown", "0"])
+            // Password hashing is not measured.
+            .args(["--argon2-memory-cost", "8", "--argon2-time-cost", "1"])
+            .env_remove("DURAK_CONFIG")
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("server should start");
+        Self { process, address }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+struct BenchClient {
+    client: Client<HttpConnector>,
+    address: SocketAddr,
+}
+
+impl BenchClient {
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&Token>,
+        body: Option<impl Serialize>,
+    ) -> T {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("http://{}{path}", self.address))
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token.as_str()));
+        }
+        let body = match body {
+            Some(body) => Body::from(serde_json::to_vec(&body).unwrap()),
+            None => Body::empty(),
+        };
+        let response = self.client.request(request.body(body).unwrap()).await;
+        let response = response.expect("request should succeed");
+        assert!(
+            response.status().is_success(),
+            "{path}: {}",
+            response.status()
+        );
+        let bytes = body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    async fn wait_until_ready(&self) {
+        let started = Instant::now();
+        let uri = format!("http://{}/healthz", self.address);
+        while self.client.get(uri.parse().unwrap()).await.is_err() {
+            assert!(
+                started.elapsed() < Duration::from_secs(10),
+                "server didn't start"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Creates started game and returns tokens of both players.
+    async fn start_game(&self, index: usize) -> [Token; 2] {
+        let CreateGameResponse::Ok { game_id, token, .. } = self
+            .send(
+                Method::POST,
+                "/create",
+                None,
+                Some(CreateGameData {
+                    name: format!("host{index}"),
+                    password: String::from("password"),
+                    rules: Rules::default(),
+                    public: false,
+                }),
+            )
+            .await;
+        let JoinGameResponse::Ok {
+            token: other_token, ..
+        } = self
+            .send(
+                Method::POST,
+                "/join",
+                None,
+                Some(JoinGameData {
+                    id: game_id,
+                    name: format!("guest{index}"),
+                    password: String::from("password"),
+                }),
+            )
+            .await;
+        for token in [&token, &other_token] {
+            let ready: bool = self
+                .send(Method::POST, "/game/ready", Some(token), None::<()>)
+                .await;
+            assert!(ready);
+        }
+        self.send::<()>(Method::POST, "/game/start", Some(&token), None::<()>)
+            .await;
+        [token, other_token]
+    }
+}
+
+/// Polls status with provided tokens until stopped.
+async fn poll(
+    client: Arc<BenchClient>,
+    tokens: Vec<Token>,
+    stop: Arc<AtomicBool>,
+    count: Arc<AtomicU64>,
+) {
+    for token in tokens.iter().cycle() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        let status: StatusResponse = client
+            .send(Method::GET, "/game/status", Some(token), None::<()>)
+            .await;
+        assert!(matches!(status, StatusResponse::Round(_)));
+        count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+async fn measure(client: &Arc<BenchClient>, games: usize) {
+    let mut tokens = Vec::with_capacity(games * 2);
+    for index in 0..games {
+        tokens.extend(client.start_game(index).await);
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let count = Arc::new(AtomicU64::new(0));
+    let tasks: Vec<_> = (0..CLIENTS)
+        .map(|client_index| {
+            // Every client polls its own share of games.
+            let tokens = tokens
+                .iter()
+                .skip(client_index % tokens.len())
+                .step_by(CLIENTS.min(tokens.len()))
+                .cloned()
+                .collect();
+            tokio::spawn(poll(client.clone(), tokens, stop.clone(), count.clone()))
+        })
+        .collect();
+
+    let started = Instant::now();
+    tokio::time::sleep(DURATION).await;
+    stop.store(true, Ordering::Relaxed);
+    for task in tasks {
+        task.await.unwrap();
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    let count = count.load(Ordering::Relaxed);
+
+    println!(
+        "{games:>6} games: {:>10.0} requests/s, {:>8.1} µs mean latency",
+        count as f64 / elapsed,
+        elapsed * 1e6 * CLIENTS as f64 / count as f64,
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let server = Server::start();
+    let client = Arc::new(BenchClient {
+        client: Client::new(),
+        address: server.address,
+    });
+    client.wait_until_ready().await;
+
+    println!("status polling with {CLIENTS} concurrent clients:");
+    for games in GAME_COUNTS {
+        measure(&client, games).await;
+    }
+}
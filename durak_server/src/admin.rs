@@ -0,0 +1,155 @@
+//! Endpoints used to operate the server.
+//!
+//! They are authorized with admin key and are disabled unless it is configured.
+
+use axum::extract::State;
+#[cfg(feature = "openapi")]
+use durak_lib::errors::ErrorResponse;
+use durak_lib::{
+    admin::{FinishQuery, GameQuery, GameSummary, PlayerQuery},
+    errors::{ApiError, InvalidPhase},
+};
+use tracing::info;
+
+use crate::{
+    auth::AuthenticateAdmin,
+    extract::{Json, Query},
+    game::GamePhase,
+    metrics::Metrics,
+    state::{Auth, Games},
+};
+
+/// Lists all games hosted by the server.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/admin/games",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "All games", body = Vec<GameSummary>),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+    ),
+))]
+pub async fn list_games(
+    State(games): State<Games>,
+    _: AuthenticateAdmin,
+) -> Json<Vec<GameSummary>> {
+    Json(games.summaries())
+}
+
+/// Returns full state of the game, including hands of all players and the deck.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/admin/game",
+    params(GameQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "State of the game", body = serde_json::Value),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+pub async fn inspect_game(
+    Query(query): Query<GameQuery>,
+    State(games): State<Games>,
+    _: AuthenticateAdmin,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    Ok(Json(games.inspect(query.id)?))
+}
+
+/// Finishes ongoing round.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/admin/finish",
+    params(FinishQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Game finished", body = ()),
+        (status = 400, description = "Game is not started or winner is not a player", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+pub async fn finish_game(
+    Query(query): Query<FinishQuery>,
+    State(games): State<Games>,
+    State(metrics): State<Metrics>,
+    _: AuthenticateAdmin,
+) -> Result<Json<()>, ApiError> {
+    let winner = games.with_game(query.id, |game| {
+        let round = game.round_state()?;
+        let winner = query.winner.unwrap_or_else(|| round.leader());
+        if !round.players.iter().any(|player| player.id == winner) {
+            return Err(ApiError::BadRequest(format!(
+                "player #{winner} is not in the game"
+            )));
+        }
+        game.finish(winner)?;
+        Ok(winner)
+    })??;
+    metrics.games_finished.inc();
+    info!("admin finished game `{}`, player #{winner} won", query.id);
+    Ok(Json(()))
+}
+
+/// Removes player from the lobby.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/admin/kick",
+    params(PlayerQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Player removed", body = ()),
+        (status = 400, description = "Game is already started or player is not in the game", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+pub async fn kick_player(
+    Query(query): Query<PlayerQuery>,
+    State(games): State<Games>,
+    State(auth): State<Auth>,
+    _: AuthenticateAdmin,
+) -> Result<Json<()>, ApiError> {
+    games.with_game(query.id, |game| {
+        let GamePhase::Lobby(lobby) = &mut game.phase else {
+            return Err(ApiError::from(InvalidPhase));
+        };
+        if !lobby.remove_player(query.player) {
+            return Err(ApiError::BadRequest(format!(
+                "player #{} is not in the game",
+                query.player
+            )));
+        }
+        Ok(())
+    })??;
+    auth.revoke_tokens(query.id, query.player);
+    info!(
+        "admin kicked player #{} from game `{}`",
+        query.player, query.id
+    );
+    Ok(Json(()))
+}
+
+/// Deletes the game.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/admin/delete",
+    params(GameQuery),
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Game deleted", body = ()),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+pub async fn delete_game(
+    Query(query): Query<GameQuery>,
+    State(games): State<Games>,
+    State(auth): State<Auth>,
+    _: AuthenticateAdmin,
+) -> Result<Json<()>, ApiError> {
+    games.remove(query.id)?;
+    auth.forget_game(query.id);
+    info!("admin deleted game `{}`", query.id);
+    Ok(Json(()))
+}
@@ -1,75 +1,146 @@
+pub mod token;
+
 use axum::{
     async_trait,
     extract::FromRequestParts,
-    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+    http::{header::AUTHORIZATION, request::Parts},
 };
 use durak_lib::{
+    errors::{ApiError, AuthFailed},
     identifiers::{GameId, PlayerId},
     network::AuthHeader,
 };
 
 use crate::state::AppState;
 
+use self::token::Claims;
+
 /// Extractor that authenticates player.
 pub struct Authenticate(pub AuthentificatedPlayer);
 
 #[async_trait]
 impl FromRequestParts<AppState> for Authenticate {
-    type Rejection = (StatusCode, &'static str);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        const REJECTION: (StatusCode, &str) = (StatusCode::UNAUTHORIZED, "Authorization failed");
-
-        let Some(header) = parts.headers.get(AUTHORIZATION) else {
-            tracing::debug!("Failed auth");
-            return Err(REJECTION);
-        };
-        let Ok(header) = header.to_str() else {
-            tracing::debug!("Failed auth");
-            return Err(REJECTION);
-        };
-        let Ok(AuthHeader { game_id, player_id, token }) = serde_json::from_str::<AuthHeader>(header) else {
-            tracing::debug!("Failed auth");
-            return Err(REJECTION);
-        };
-        if !state.auth.validate_session(token, game_id, player_id) {
-            tracing::debug!("Failed auth for game `{game_id}`");
-            return Err(REJECTION);
-        }
+        authenticate(parts, state).map_err(|err| {
+            tracing::debug!("Failed auth: {err}");
+            state.metrics.auth_failed(&err);
+            err
+        })
+    }
+}
+
+fn authenticate(parts: &Parts, state: &AppState) -> Result<Authenticate, ApiError> {
+    let claims = validate_token(parts, state)?;
+    let player_id = claims.player_id.ok_or(AuthFailed::Spectator)?;
+
+    Ok(Authenticate(AuthentificatedPlayer {
+        game_id: claims.game_id,
+        player_id,
+    }))
+}
 
-        Ok(Authenticate(AuthentificatedPlayer { game_id, player_id }))
+fn validate_token(parts: &Parts, state: &AppState) -> Result<Claims, ApiError> {
+    let header = parts
+        .headers
+        .get(AUTHORIZATION)
+        .ok_or(AuthFailed::NoAuthHeader)?;
+    let AuthHeader(token) = AuthHeader::from_header(header)?;
+    state.validate_token(&token)
+}
+
+/// Extractor that authenticates spectator of the game.
+///
+/// Tokens of players are accepted as well, since spectator status reveals nothing new to them.
+pub struct AuthenticateSpectator(pub GameId);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthenticateSpectator {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        validate_token(parts, state)
+            .map(|claims| AuthenticateSpectator(claims.game_id))
+            .map_err(|err| {
+                tracing::debug!("Failed spectator auth: {err}");
+                state.metrics.auth_failed(&err);
+                err
+            })
     }
 }
 
 /// Extractor that authenticates player and ensures that it is host of the game.
+///
+/// Only lobbies have a host, requests to games in other phases are rejected.
 pub struct AuthenticateHost(pub AuthentificatedPlayer);
 
 #[async_trait]
 impl FromRequestParts<AppState> for AuthenticateHost {
-    type Rejection = (StatusCode, &'static str);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        const REJECTION: (StatusCode, &str) = (StatusCode::UNAUTHORIZED, "Authorization failed");
-
         let Authenticate(authenticated) = Authenticate::from_request_parts(parts, state).await?;
-        if authenticated.player_id.is_host() {
+        let is_host = state
+            .games
+            .with_lobby_game(authenticated.game_id, |lobby| {
+                lobby.host() == Some(authenticated.player_id)
+            })?;
+        if is_host {
             Ok(AuthenticateHost(authenticated))
         } else {
             tracing::info!(
                 "Failed auth for game `{}`: not a host",
                 authenticated.game_id
             );
-            Err(REJECTION)
+            let err = AuthFailed::NotHost.into();
+            state.metrics.auth_failed(&err);
+            Err(err)
         }
     }
 }
 
+/// Extractor that authorizes request with admin key.
+pub struct AuthenticateAdmin;
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthenticateAdmin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if !state.auth.is_admin_enabled() {
+            return Err(ApiError::NotFound);
+        }
+        authenticate_admin(parts, state).map_err(|err| {
+            tracing::warn!("Failed admin auth: {err}");
+            state.metrics.auth_failed(&err);
+            err
+        })
+    }
+}
+
+fn authenticate_admin(parts: &Parts, state: &AppState) -> Result<AuthenticateAdmin, ApiError> {
+    let header = parts
+        .headers
+        .get(AUTHORIZATION)
+        .ok_or(AuthFailed::NoAuthHeader)?;
+    let AuthHeader(key) = AuthHeader::from_header(header)?;
+    state.auth.validate_admin_key(key.as_str())?;
+    Ok(AuthenticateAdmin)
+}
+
 #[derive(Debug)]
 pub struct AuthentificatedPlayer {
     pub game_id: GameId,
@@ -0,0 +1,175 @@
+//! Signed session tokens.
+//!
+//! Token consists of two parts separated by a dot: base64-encoded json [Claims]
+//! and base64-encoded HMAC-SHA256 signature of the first part.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use durak_lib::{
+    errors::AuthFailed,
+    identifiers::{GameId, PlayerId},
+    network::Token,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Data carried by the token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claims {
+    pub game_id: GameId,
+    /// Nonce of the game, so tokens don't carry over to a new game with the same id.
+    pub game_nonce: u64,
+    /// Seat of the player, `None` for spectators.
+    pub player_id: Option<PlayerId>,
+    /// Unix timestamp of token creation.
+    pub issued_at: u64,
+    /// Unix timestamp after which token is no longer valid.
+    pub expires_at: u64,
+}
+
+/// Issuer and verifier of signed tokens.
+#[derive(Clone)]
+pub struct TokenSigner {
+    mac: Hmac<Sha256>,
+    lifetime: Duration,
+}
+
+impl TokenSigner {
+    /// Creates new signer with provided key.
+    ///
+    /// Tokens are valid for `lifetime` after being issued.
+    pub fn new(key: &[u8], lifetime: Duration) -> Self {
+        Self {
+            mac: Hmac::new_from_slice(key).expect("HMAC should accept key of any size"),
+            lifetime,
+        }
+    }
+
+    /// Issues new token for the player.
+    pub fn issue(&self, game_id: GameId, game_nonce: u64, player_id: PlayerId) -> Token {
+        self.issue_claims(game_id, game_nonce, Some(player_id))
+    }
+
+    /// Issues new read-only token for the spectator.
+    pub fn issue_spectator(&self, game_id: GameId, game_nonce: u64) -> Token {
+        self.issue_claims(game_id, game_nonce, None)
+    }
+
+    fn issue_claims(&self, game_id: GameId, game_nonce: u64, player_id: Option<PlayerId>) -> Token {
+        let issued_at = unix_now();
+        let claims = Claims {
+            game_id,
+            game_nonce,
+            player_id,
+            issued_at,
+            expires_at: issued_at.saturating_add(self.lifetime.as_secs()),
+        };
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+        let signature = URL_SAFE_NO_PAD.encode(self.sign(&payload));
+        Token::new(format!("{payload}.{signature}"))
+    }
+
+    /// Verifies signature and expiration time of the token.
+    ///
+    /// Returns claims carried by the token if it is valid.
+    pub fn verify(&self, token: &Token) -> Result<Claims, AuthFailed> {
+        let (payload, signature) = token
+            .as_str()
+            .split_once('.')
+            .ok_or(AuthFailed::TokenDoesntMatch)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| AuthFailed::TokenDoesntMatch)?;
+
+        let mut mac = self.mac.clone();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| AuthFailed::TokenDoesntMatch)?;
+
+        let claims = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|payload| serde_json::from_slice::<Claims>(&payload).ok())
+            .ok_or(AuthFailed::TokenDoesntMatch)?;
+        if claims.expires_at <= unix_now() {
+            return Err(AuthFailed::TokenExpired);
+        }
+        Ok(claims)
+    }
+
+    fn sign(&self, payload: &str) -> Vec<u8> {
+        let mut mac = self.mac.clone();
+        mac.update(payload.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+/// Returns current unix timestamp in seconds.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use durak_lib::{
+        errors::AuthFailed,
+        identifiers::{GameId, PlayerId},
+        network::Token,
+    };
+
+    use super::TokenSigner;
+
+    #[test]
+    fn test_token_roundtrip() {
+        let signer = TokenSigner::new(b"secret", Duration::from_secs(60));
+        let token = signer.issue(GameId::new(42), 7, PlayerId::new(1));
+        let claims = signer.verify(&token).unwrap();
+        assert_eq!(claims.game_id, GameId::new(42));
+        assert_eq!(claims.player_id, Some(PlayerId::new(1)));
+
+        let token = signer.issue_spectator(GameId::new(42), 7);
+        assert_eq!(signer.verify(&token).unwrap().player_id, None);
+    }
+
+    #[test]
+    fn test_token_signed_with_other_key() {
+        let signer = TokenSigner::new(b"secret", Duration::from_secs(60));
+        let other = TokenSigner::new(b"other secret", Duration::from_secs(60));
+        let token = other.issue(GameId::new(42), 7, PlayerId::new(1));
+        assert!(matches!(
+            signer.verify(&token),
+            Err(AuthFailed::TokenDoesntMatch)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_token() {
+        let signer = TokenSigner::new(b"secret", Duration::from_secs(60));
+        let token = signer.issue(GameId::new(42), 7, PlayerId::new(1));
+        let (_, signature) = token.as_str().split_once('.').unwrap();
+        let forged = signer.issue(GameId::new(42), 7, PlayerId::new(0));
+        let (payload, _) = forged.as_str().split_once('.').unwrap();
+        let tampered = Token::new(format!("{payload}.{signature}"));
+        assert!(matches!(
+            signer.verify(&tampered),
+            Err(AuthFailed::TokenDoesntMatch)
+        ));
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let signer = TokenSigner::new(b"secret", Duration::ZERO);
+        let token = signer.issue(GameId::new(42), 7, PlayerId::new(1));
+        assert!(matches!(
+            signer.verify(&token),
+            Err(AuthFailed::TokenExpired)
+        ));
+    }
+}
@@ -0,0 +1,638 @@
+//! Configuration of the server.
+//!
+//! Every setting is resolved from the following sources, each one overriding the previous:
+//!
+//! 1. built-in defaults;
+//! 2. config file passed with `--config`;
+//! 3. environment variables;
+//! 4. command-line flags.
+
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Parser, ValueEnum};
+use rand::{thread_rng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+/// Command-line arguments of the server.
+///
+/// Every option may also be set with environment variable.
+#[derive(Debug, Default, Parser)]
+#[command(version, about = "Server of the Durak card game")]
+pub struct Args {
+    /// Path to the config file.
+    #[arg(short, long, env = "DURAK_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Print effective configuration and exit.
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Address the server listens on.
+    #[arg(long, env = "DURAK_SERVER_ADDRESS")]
+    pub address: Option<SocketAddr>,
+    /// Directory where data that should survive restart is kept.
+    #[arg(long, env = "DURAK_DATA_DIR")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Log filter, e.g. `info` or `warn,durak_server=debug`.
+    #[arg(long, env = "DURAK_LOG_LEVEL")]
+    pub log_level: Option<String>,
+    /// Format of log messages.
+    #[arg(long, env = "DURAK_LOG_FORMAT")]
+    pub log_format: Option<LogFormat>,
+
+    /// Maximum number of games hosted at the same time.
+    #[arg(long, env = "DURAK_MAX_GAMES")]
+    pub max_games: Option<usize>,
+    /// Maximum length of player name in characters.
+    #[arg(long, env = "DURAK_MAX_NAME_LENGTH")]
+    pub max_name_length: Option<usize>,
+    /// Maximum length of chat message in characters.
+    #[arg(long, env = "DURAK_MAX_MESSAGE_LENGTH")]
+    pub max_message_length: Option<usize>,
+    /// Seconds between all players getting ready and the host being able to start the game.
+    #[arg(long, env = "DURAK_START_COUNTDOWN", value_name = "SECONDS")]
+    pub start_countdown: Option<u64>,
+
+    /// Seconds of inactivity after which lobby is removed.
+    #[arg(long, env = "DURAK_LOBBY_EXPIRY", value_name = "SECONDS")]
+    pub lobby_expiry: Option<u64>,
+    /// Seconds of inactivity after which ongoing game is removed.
+    #[arg(long, env = "DURAK_ROUND_EXPIRY", value_name = "SECONDS")]
+    pub round_expiry: Option<u64>,
+    /// Seconds after which finished game is removed.
+    #[arg(long, env = "DURAK_FINISHED_EXPIRY", value_name = "SECONDS")]
+    pub finished_expiry: Option<u64>,
+    /// Seconds during which issued session token stays valid.
+    #[arg(long, env = "DURAK_TOKEN_LIFETIME", value_name = "SECONDS")]
+    pub token_lifetime: Option<u64>,
+
+    /// Path to PEM-encoded TLS certificate chain.
+    #[arg(long, env = "DURAK_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+    /// Path to PEM-encoded TLS private key.
+    #[arg(long, env = "DURAK_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Argon2 memory size in KiB.
+    #[arg(long, env = "DURAK_ARGON2_MEMORY_COST")]
+    pub argon2_memory_cost: Option<u32>,
+    /// Argon2 number of iterations.
+    #[arg(long, env = "DURAK_ARGON2_TIME_COST")]
+    pub argon2_time_cost: Option<u32>,
+    /// Argon2 degree of parallelism.
+    #[arg(long, env = "DURAK_ARGON2_PARALLELISM")]
+    pub argon2_parallelism: Option<u32>,
+
+    /// Maximum number of `/create` and `/join` requests from single IP address per period.
+    #[arg(long, env = "DURAK_RATE_LIMIT_IP")]
+    pub rate_limit_ip: Option<u32>,
+    /// Maximum number of attempts to join single game from single IP address per period.
+    #[arg(long, env = "DURAK_RATE_LIMIT_GAME")]
+    pub rate_limit_game: Option<u32>,
+    /// Maximum number of chat messages from single player per period.
+    #[arg(long, env = "DURAK_RATE_LIMIT_CHAT")]
+    pub rate_limit_chat: Option<u32>,
+    /// Seconds over which requests are counted.
+    #[arg(long, env = "DURAK_RATE_LIMIT_PERIOD", value_name = "SECONDS")]
+    pub rate_limit_period: Option<u64>,
+    /// Number of wrong passwords in a row after which IP address is locked out of the game.
+    #[arg(long, env = "DURAK_LOCKOUT_THRESHOLD")]
+    pub lockout_threshold: Option<u32>,
+    /// Seconds during which locked out IP address can't join the game.
+    #[arg(long, env = "DURAK_LOCKOUT_DURATION", value_name = "SECONDS")]
+    pub lockout_duration: Option<u64>,
+
+    /// Key that authorizes admin requests. Admin endpoints are disabled if it is not set.
+    ///
+    /// Prefer environment variable, so the key doesn't show up in process list.
+    #[arg(long, env = "DURAK_ADMIN_KEY", hide_env_values = true)]
+    pub admin_key: Option<String>,
+
+    /// Seed of deck shuffling, so every game deals the same cards. Intended for testing.
+    #[arg(long, env = "DURAK_DECK_SEED")]
+    pub deck_seed: Option<u64>,
+}
+
+/// Configuration of the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Address the server listens on.
+    pub address: SocketAddr,
+    /// Directory where data that should survive restart is kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+    /// Logging settings.
+    pub log: LogConfig,
+    /// Limits of hosted games.
+    pub limits: GameLimits,
+    /// Time after which unused games and tokens expire.
+    pub expiry: Expiry,
+    /// TLS settings. Server uses plain HTTP if they are not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Parameters of the lobby password hashing.
+    pub password_hashing: PasswordHashing,
+    /// Limits for creating and joining games.
+    pub rate_limits: RateLimits,
+    /// Seed of deck shuffling, so every game deals the same cards. Intended for testing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deck_seed: Option<u64>,
+    /// Secret key used to sign session tokens.
+    ///
+    /// It is never read from the config file, see [Config::load_token_key].
+    #[serde(skip)]
+    pub token_key: Vec<u8>,
+    /// Key that authorizes admin requests.
+    ///
+    /// It is never read from the config file to keep it out of `--print-config` output.
+    #[serde(skip)]
+    pub admin_key: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut token_key = vec![0; 32];
+        thread_rng().fill_bytes(&mut token_key);
+        Self {
+            address: env!("DURAK_SERVER_ADDRESS")
+                .parse()
+                .expect("default listening address should be valid"),
+            data_dir: None,
+            log: LogConfig::default(),
+            limits: GameLimits::default(),
+            expiry: Expiry::default(),
+            tls: None,
+            password_hashing: PasswordHashing::default(),
+            rate_limits: RateLimits::default(),
+            deck_seed: None,
+            token_key,
+            admin_key: None,
+        }
+    }
+}
+
+impl Config {
+    /// Name of the file in data directory where token key is kept.
+    const TOKEN_KEY_FILE: &'static str = "token.key";
+    /// Minimal length of admin key.
+    const MIN_ADMIN_KEY_LENGTH: usize = 16;
+
+    /// Resolves configuration from config file, environment variables and provided arguments.
+    ///
+    /// Resolved configuration is validated.
+    pub fn load(args: Args) -> Result<Self, ConfigError> {
+        let mut config = match &args.config {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.apply(args)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads config file.
+    ///
+    /// Settings missing from the file are set to default values.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    /// Overrides settings with values of provided arguments.
+    fn apply(&mut self, args: Args) -> Result<(), ConfigError> {
+        fn set<T>(target: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *target = value;
+            }
+        }
+        fn set_secs(target: &mut Duration, value: Option<u64>) {
+            set(target, value.map(Duration::from_secs));
+        }
+
+        set(&mut self.address, args.address);
+        if args.data_dir.is_some() {
+            self.data_dir = args.data_dir;
+        }
+
+        set(&mut self.log.level, args.log_level);
+        set(&mut self.log.format, args.log_format);
+
+        set(&mut self.limits.max_games, args.max_games);
+        set(&mut self.limits.max_name_length, args.max_name_length);
+        set(&mut self.limits.max_message_length, args.max_message_length);
+        set_secs(&mut self.limits.start_countdown, args.start_countdown);
+
+        set_secs(&mut self.expiry.lobby, args.lobby_expiry);
+        set_secs(&mut self.expiry.round, args.round_expiry);
+        set_secs(&mut self.expiry.finished, args.finished_expiry);
+        set_secs(&mut self.expiry.token, args.token_lifetime);
+
+        match (args.tls_cert, args.tls_key, &mut self.tls) {
+            (cert, key, Some(tls)) => {
+                set(&mut tls.cert, cert);
+                set(&mut tls.key, key);
+            }
+            (Some(cert), Some(key), None) => self.tls = Some(TlsConfig { cert, key }),
+            (None, None, None) => {}
+            _ => {
+                return Err(ConfigError::Invalid(String::from(
+                    "both TLS certificate and key must be set",
+                )))
+            }
+        }
+
+        let hashing = &mut self.password_hashing;
+        set(&mut hashing.memory_cost, args.argon2_memory_cost);
+        set(&mut hashing.time_cost, args.argon2_time_cost);
+        set(&mut hashing.parallelism, args.argon2_parallelism);
+
+        let limits = &mut self.rate_limits;
+        set(&mut limits.per_ip, args.rate_limit_ip);
+        set(&mut limits.per_game, args.rate_limit_game);
+        set(&mut limits.chat, args.rate_limit_chat);
+        set_secs(&mut limits.period, args.rate_limit_period);
+        set(&mut limits.lockout_threshold, args.lockout_threshold);
+        set_secs(&mut limits.lockout_duration, args.lockout_duration);
+
+        if args.admin_key.is_some() {
+            self.admin_key = args.admin_key;
+        }
+        if args.deck_seed.is_some() {
+            self.deck_seed = args.deck_seed;
+        }
+        Ok(())
+    }
+
+    /// Checks that all settings have valid values.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        fn check(condition: bool, message: &str) -> Result<(), ConfigError> {
+            if condition {
+                Ok(())
+            } else {
+                Err(ConfigError::Invalid(message.to_owned()))
+            }
+        }
+
+        if let Err(err) = EnvFilter::try_new(&self.log.level) {
+            return Err(ConfigError::Invalid(format!("invalid log level: {err}")));
+        }
+
+        let limits = &self.limits;
+        check(limits.max_games > 0, "`max_games` must be positive")?;
+        check(
+            limits.max_name_length > 0,
+            "`max_name_length` must be positive",
+        )?;
+        check(
+            limits.max_message_length > 0,
+            "`max_message_length` must be positive",
+        )?;
+
+        let expiry = &self.expiry;
+        for (value, name) in [
+            (expiry.lobby, "lobby"),
+            (expiry.round, "round"),
+            (expiry.finished, "finished"),
+            (expiry.token, "token"),
+        ] {
+            check(
+                !value.is_zero(),
+                &format!("`expiry.{name}` must be positive"),
+            )?;
+        }
+
+        if let Some(data_dir) = &self.data_dir {
+            check(
+                !data_dir.exists() || data_dir.is_dir(),
+                "`data_dir` must be a directory",
+            )?;
+        }
+
+        if let Some(tls) = &self.tls {
+            check(tls.cert.is_file(), "TLS certificate file doesn't exist")?;
+            check(tls.key.is_file(), "TLS key file doesn't exist")?;
+        }
+
+        let hashing = &self.password_hashing;
+        if let Err(err) = argon2::Params::new(
+            hashing.memory_cost,
+            hashing.time_cost,
+            hashing.parallelism,
+            None,
+        ) {
+            return Err(ConfigError::Invalid(format!(
+                "invalid password hashing parameters: {err}"
+            )));
+        }
+
+        let limits = &self.rate_limits;
+        check(limits.per_ip > 0, "`rate_limits.per_ip` must be positive")?;
+        check(
+            limits.per_game > 0,
+            "`rate_limits.per_game` must be positive",
+        )?;
+        check(limits.chat > 0, "`rate_limits.chat` must be positive")?;
+        check(
+            !limits.period.is_zero(),
+            "`rate_limits.period` must be positive",
+        )?;
+        check(
+            limits.lockout_threshold > 0,
+            "`rate_limits.lockout_threshold` must be positive",
+        )?;
+
+        if let Some(key) = &self.admin_key {
+            check(
+                key.len() >= Self::MIN_ADMIN_KEY_LENGTH,
+                "admin key must be at least 16 characters long",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Loads secret key used to sign session tokens.
+    ///
+    /// Key is taken from `DURAK_SERVER_KEY` environment variable if it is set.
+    /// Otherwise, it is read from data directory, where it is generated on first start.
+    /// If there is no data directory, random key is used.
+    pub fn load_token_key(&mut self) -> Result<(), ConfigError> {
+        if let Ok(key) = std::env::var("DURAK_SERVER_KEY") {
+            self.token_key = key.into_bytes();
+            return Ok(());
+        }
+        let Some(data_dir) = &self.data_dir else {
+            warn!("neither `DURAK_SERVER_KEY` nor data directory is set, issued tokens won't survive restart");
+            return Ok(());
+        };
+
+        let path = data_dir.join(Self::TOKEN_KEY_FILE);
+        let io_error = |source| ConfigError::Io {
+            path: path.clone(),
+            source,
+        };
+        if path.exists() {
+            self.token_key = fs::read(&path).map_err(io_error)?;
+        } else {
+            fs::create_dir_all(data_dir).map_err(io_error)?;
+            fs::write(&path, &self.token_key).map_err(io_error)?;
+            info!("generated new token key at `{}`", path.display());
+        }
+        Ok(())
+    }
+
+    /// Returns configuration in config file format.
+    ///
+    /// Token key is never included.
+    pub fn to_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("config should be serializable")
+    }
+}
+
+/// Failed to load configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to access `{}`: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse `{}`: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("invalid configuration: {0}")]
+    Invalid(String),
+}
+
+/// Logging settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    /// Log filter in `RUST_LOG` format.
+    pub level: String,
+    /// Format of log messages.
+    pub format: LogFormat,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: String::from("info"),
+            format: LogFormat::Text,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Installs global logger.
+    pub fn init(&self) {
+        let builder = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(&self.level));
+        match self.format {
+            LogFormat::Text => builder.init(),
+            LogFormat::Json => builder.json().init(),
+        }
+    }
+}
+
+/// Format of log messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable text.
+    Text,
+    /// One json object per line.
+    Json,
+}
+
+/// Limits of hosted games.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameLimits {
+    /// Maximum number of games hosted at the same time.
+    pub max_games: usize,
+    /// Maximum length of player name in characters.
+    pub max_name_length: usize,
+    /// Maximum length of chat message in characters.
+    pub max_message_length: usize,
+    /// Time between all players getting ready and the host being able to start the game.
+    #[serde(with = "seconds")]
+    pub start_countdown: Duration,
+}
+
+impl Default for GameLimits {
+    fn default() -> Self {
+        Self {
+            max_games: 1024,
+            max_name_length: 32,
+            max_message_length: 200,
+            start_countdown: Duration::ZERO,
+        }
+    }
+}
+
+/// Time after which unused games and tokens expire.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Expiry {
+    /// Inactivity time after which lobby is removed.
+    #[serde(with = "seconds")]
+    pub lobby: Duration,
+    /// Inactivity time after which ongoing game is removed.
+    #[serde(with = "seconds")]
+    pub round: Duration,
+    /// Time after which finished game is removed.
+    #[serde(with = "seconds")]
+    pub finished: Duration,
+    /// Time during which issued session token stays valid.
+    #[serde(with = "seconds")]
+    pub token: Duration,
+}
+
+impl Default for Expiry {
+    fn default() -> Self {
+        Self {
+            lobby: Duration::from_secs(60 * 60),
+            round: Duration::from_secs(60 * 60),
+            finished: Duration::from_secs(10 * 60),
+            token: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// TLS settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// Path to PEM-encoded certificate chain.
+    pub cert: PathBuf,
+    /// Path to PEM-encoded private key.
+    pub key: PathBuf,
+}
+
+/// Parameters of Argon2id that is used to hash lobby passwords.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordHashing {
+    /// Memory size in KiB.
+    pub memory_cost: u32,
+    /// Number of iterations.
+    pub time_cost: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashing {
+    fn default() -> Self {
+        Self {
+            memory_cost: argon2::Params::DEFAULT_M_COST,
+            time_cost: argon2::Params::DEFAULT_T_COST,
+            parallelism: argon2::Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Limits for requests to `/create`, `/join` and the chat.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimits {
+    /// Maximum number of requests from single IP address per period.
+    pub per_ip: u32,
+    /// Maximum number of attempts to join single game from single IP address per period.
+    pub per_game: u32,
+    /// Maximum number of chat messages from single player per period.
+    pub chat: u32,
+    /// Period over which requests are counted.
+    #[serde(with = "seconds")]
+    pub period: Duration,
+    /// Number of wrong passwords in a row after which IP address is locked out of the game.
+    pub lockout_threshold: u32,
+    /// Time during which locked out IP address can't join the game.
+    #[serde(with = "seconds")]
+    pub lockout_duration: Duration,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            per_ip: 30,
+            per_game: 20,
+            chat: 20,
+            period: Duration::from_secs(60),
+            lockout_threshold: 5,
+            lockout_duration: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Durations are written in config file as a number of seconds.
+mod seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_secs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Args, Config};
+
+    const EXAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/config.example.toml");
+
+    #[test]
+    fn test_example_config_is_valid() {
+        let config = Config::from_file(&PathBuf::from(EXAMPLE)).unwrap();
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_flags_override_config_file() {
+        let config = Config::load(Args {
+            config: Some(PathBuf::from(EXAMPLE)),
+            max_games: Some(7),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(config.limits.max_games, 7);
+        let file = Config::from_file(&PathBuf::from(EXAMPLE)).unwrap();
+        assert_eq!(config.limits.max_name_length, file.limits.max_name_length);
+    }
+
+    #[test]
+    fn test_printed_config_roundtrip() {
+        let config = Config::default();
+        let parsed: Config = toml::from_str(&config.to_toml()).unwrap();
+        assert_eq!(parsed.to_toml(), config.to_toml());
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let mut config = Config::default();
+        config.rate_limits.per_ip = 0;
+        assert!(config.validate().is_err());
+    }
+}
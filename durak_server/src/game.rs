@@ -1,23 +1,102 @@
 //! Game state and logic.
 
+pub mod chat;
 pub mod finished;
 pub mod lobby;
 pub mod round;
 
-use durak_lib::{errors::InvalidPhase, identifiers::PlayerId};
+use std::{
+    mem,
+    time::{Duration, Instant},
+};
 
-use self::{finished::FinishedState, lobby::LobbyState, round::RoundState};
+use durak_lib::{
+    errors::{ActionError, ApiError, InvalidPhase},
+    game::rules::Rules,
+    identifiers::PlayerId,
+    status::{PlayerData, SpectatorStatus, StatusResponse},
+};
+use rand::Rng;
+use serde::Serialize;
+
+use crate::config::Expiry;
+
+use self::{chat::ChatLog, finished::FinishedState, lobby::LobbyState, round::RoundState};
 
 #[derive(Debug)]
 pub struct Game {
     pub phase: GamePhase,
+    pub chat: ChatLog,
+    /// Random value that tells this game apart from earlier games with the same id.
+    nonce: u64,
+    /// Time of the last request to the game.
+    last_activity: Instant,
 }
 
 impl Game {
-    /// Creates new game with provided password set.
-    pub fn new() -> Self {
+    /// Creates new game played by provided rules.
+    ///
+    /// Once every player is ready, the game may be started after `start_countdown`.
+    pub fn new(rules: Rules, start_countdown: Duration) -> Self {
         Self {
-            phase: GamePhase::new(),
+            phase: GamePhase::new(rules, start_countdown),
+            chat: ChatLog::default(),
+            nonce: rand::random(),
+            last_activity: Instant::now(),
+        }
+    }
+
+    /// Returns nonce of the game, which is signed into every token issued for it.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Marks game as active.
+    pub fn touch(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Returns time passed since the last request to the game.
+    pub fn idle_time(&self, now: Instant) -> Duration {
+        now.duration_since(self.last_activity)
+    }
+
+    /// Returns `true` if game wasn't active for longer than allowed in its phase.
+    pub fn is_expired(&self, now: Instant, expiry: &Expiry) -> bool {
+        let limit = match self.phase {
+            GamePhase::Lobby(_) => expiry.lobby,
+            GamePhase::Round(_) => expiry.round,
+            GamePhase::Finished(_) => expiry.finished,
+        };
+        self.idle_time(now) > limit
+    }
+
+    /// Returns all players of the game.
+    pub fn players(&self) -> &[PlayerData] {
+        match &self.phase {
+            GamePhase::Lobby(lobby) => lobby.players(),
+            GamePhase::Round(round) => &round.players,
+            GamePhase::Finished(finished) => &finished.players,
+        }
+    }
+
+    /// Generates status report for specific player.
+    pub fn status(&self, player: PlayerId) -> StatusResponse {
+        let chat = self.chat.messages();
+        match &self.phase {
+            GamePhase::Lobby(lobby) => StatusResponse::Lobby(lobby.status(chat)),
+            GamePhase::Round(round) => StatusResponse::Round(round.status(player, chat)),
+            GamePhase::Finished(finished) => StatusResponse::Finished(finished.status()),
+        }
+    }
+
+    /// Generates status report for spectators.
+    pub fn spectator_status(&self) -> SpectatorStatus {
+        let chat = self.chat.messages();
+        match &self.phase {
+            GamePhase::Lobby(lobby) => SpectatorStatus::Lobby(lobby.status(chat)),
+            GamePhase::Round(round) => SpectatorStatus::Round(round.spectator_status(chat)),
+            GamePhase::Finished(finished) => SpectatorStatus::Finished(finished.status()),
         }
     }
 
@@ -50,21 +129,70 @@ impl Game {
 
     /// Starts the game.
     ///
-    /// Deck is shuffled and cards are dealed.
+    /// Deck is shuffled with provided random number generator and cards are dealed.
     ///
-    /// Returns `true` if started successfully.
-    pub fn start(&mut self) -> bool {
-        if let GamePhase::Lobby(state) = &mut self.phase {
-            self.phase = GamePhase::Round(state.to_started());
-            true
-        } else {
-            false
+    /// Fails if the game is already started, there are not enough players
+    /// or not every player is ready.
+    pub fn start(&mut self, rng: &mut impl Rng) -> Result<(), ApiError> {
+        let state = self.lobby_state()?;
+        if state.is_full() && !state.everyone_ready() {
+            return Err(ActionError::PlayersNotReady.into());
+        }
+        self.force_start(rng)
+    }
+
+    /// Starts the game without waiting for players to get ready.
+    ///
+    /// Fails if the game is already started or there are not enough players.
+    pub fn force_start(&mut self, rng: &mut impl Rng) -> Result<(), ApiError> {
+        let state = self.lobby_state()?;
+        if !state.is_full() {
+            return Err(ActionError::NotEnoughPlayers.into());
+        }
+        self.phase = GamePhase::Round(state.to_started(rng));
+        Ok(())
+    }
+
+    /// Acts on behalf of the player on turn if their time is over.
+    ///
+    /// Returns `true` if the turn timed out.
+    pub fn apply_turn_timeout(&mut self) -> bool {
+        match &mut self.phase {
+            GamePhase::Round(round) => round.apply_timeout(),
+            _ => false,
         }
     }
+
+    /// Finishes the round, declaring provided player a winner.
+    pub fn finish(&mut self, winner: PlayerId) -> Result<(), InvalidPhase> {
+        let round = self.round_state()?;
+        self.phase = GamePhase::Finished(FinishedState {
+            winner,
+            players: mem::take(&mut round.players),
+            forfeited: None,
+        });
+        Ok(())
+    }
+
+    /// Finishes the round because provided player left it, the other player wins.
+    ///
+    /// Returns the winner.
+    pub fn forfeit(&mut self, player: PlayerId) -> Result<PlayerId, InvalidPhase> {
+        let round = self.round_state()?;
+        let winner = round
+            .players
+            .iter()
+            .map(|data| data.id)
+            .find(|id| *id != player)
+            .expect("round should have at least two players");
+        self.finish(winner)?;
+        self.finished_state()?.forfeited = Some(player);
+        Ok(winner)
+    }
 }
 
 /// Phase-specific state of the game.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub enum GamePhase {
     /// Preparation phase of the game.
     Lobby(LobbyState),
@@ -75,8 +203,8 @@ pub enum GamePhase {
 }
 
 impl GamePhase {
-    pub fn new() -> Self {
-        GamePhase::Lobby(LobbyState::new())
+    pub fn new(rules: Rules, start_countdown: Duration) -> Self {
+        GamePhase::Lobby(LobbyState::new(rules, start_countdown))
     }
 }
 
@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+
+use durak_lib::{chat::ChatMessage, status::PlayerData};
+use serde::Serialize;
+
+/// Chat of the game, keeping only the latest messages.
+#[derive(Debug, Default, Serialize)]
+pub struct ChatLog {
+    messages: VecDeque<ChatMessage>,
+    next_id: u32,
+}
+
+impl ChatLog {
+    /// Number of messages that are kept and sent with the status.
+    const CAPACITY: usize = 50;
+
+    /// Posts message of the player, dropping the oldest one if the log is full.
+    pub fn post(&mut self, author: &PlayerData, text: String) -> &ChatMessage {
+        if self.messages.len() >= Self::CAPACITY {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(ChatMessage {
+            id: self.next_id,
+            author: author.id,
+            name: author.name.clone(),
+            text,
+        });
+        self.next_id += 1;
+        self.messages.back().unwrap()
+    }
+
+    /// Returns kept messages from the oldest to the newest.
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.messages.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use durak_lib::{identifiers::PlayerId, status::PlayerData};
+
+    use super::ChatLog;
+
+    #[test]
+    fn test_chat_log_keeps_latest_messages() {
+        let author = PlayerData {
+            id: PlayerId::new(0),
+            name: String::from("host"),
+            seat: 0,
+        };
+        let mut chat = ChatLog::default();
+        for i in 0..ChatLog::CAPACITY + 5 {
+            chat.post(&author, i.to_string());
+        }
+        let messages = chat.messages();
+        assert_eq!(messages.len(), ChatLog::CAPACITY);
+        assert_eq!(messages[0].id, 5);
+        assert_eq!(messages[0].text, "5");
+    }
+}
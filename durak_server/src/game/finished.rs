@@ -2,11 +2,14 @@ use durak_lib::{
     identifiers::PlayerId,
     status::{finished::FinishedStatus, PlayerData},
 };
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct FinishedState {
     pub winner: PlayerId,
     pub players: Vec<PlayerData>,
+    /// Player that ended the game by leaving it.
+    pub forfeited: Option<PlayerId>,
 }
 
 impl FinishedState {
@@ -15,6 +18,7 @@ impl FinishedState {
         FinishedStatus {
             winner: self.winner,
             players: self.players.clone(),
+            forfeited: self.forfeited,
         }
     }
 
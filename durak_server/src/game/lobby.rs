@@ -1,63 +1,308 @@
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
 use durak_lib::{
-    identifiers::PlayerId,
+    chat::ChatMessage,
+    errors::{ApiError, AuthFailed},
+    game::rules::Rules,
+    identifiers::{GameId, PlayerId},
+    network::LobbySummary,
     status::{lobby::LobbyStatus, PlayerData},
 };
+use rand::Rng;
+use serde::Serialize;
 
 use super::round::RoundState;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct LobbyState {
+    /// Players ordered by their seats.
     players: Vec<PlayerData>,
+    /// Id given to the next player, ids are never reused.
+    next_id: u8,
+    /// Player that manages the lobby, `None` once everyone left.
+    host: Option<PlayerId>,
+    /// Players that are ready to start.
+    ready: HashSet<PlayerId>,
+    /// Time between all players getting ready and the game being allowed to start.
+    start_countdown: Duration,
+    /// Time when the last player got ready, `None` if someone is missing or not ready.
+    #[serde(skip)]
+    ready_since: Option<Instant>,
+    rules: Rules,
+    /// Whether the lobby is listed in the lobby browser.
+    public: bool,
+    /// Whether password is needed to join the lobby.
+    has_password: bool,
 }
 
 impl LobbyState {
-    /// Creates new lobby state.
-    pub(super) fn new() -> Self {
+    /// Creates new private lobby state.
+    pub(super) fn new(rules: Rules, start_countdown: Duration) -> Self {
         Self {
-            players: Vec::with_capacity(4),
+            players: Vec::with_capacity(rules.players as usize),
+            next_id: 0,
+            host: None,
+            ready: HashSet::new(),
+            start_countdown,
+            ready_since: None,
+            rules,
+            public: false,
+            has_password: false,
+        }
+    }
+
+    /// Lists the lobby in the lobby browser.
+    pub fn publish(&mut self, has_password: bool) {
+        self.public = true;
+        self.has_password = has_password;
+    }
+
+    /// Returns summary shown in the lobby browser.
+    ///
+    /// Returns `None` if lobby is not public or can't be joined.
+    pub fn summary(&self, id: GameId) -> Option<LobbySummary> {
+        if !self.public || self.is_full() {
+            return None;
         }
+        let host = self
+            .players
+            .iter()
+            .find(|player| Some(player.id) == self.host)?;
+        Some(LobbySummary {
+            id,
+            host: host.name.clone(),
+            players: self.players.len() as u8,
+            rules: self.rules.clone(),
+            has_password: self.has_password,
+        })
+    }
+
+    /// Returns `true` if no more players can join.
+    pub fn is_full(&self) -> bool {
+        self.players.len() >= self.rules.players as usize
     }
 
     /// Generates status report.
-    pub fn status(&self) -> LobbyStatus {
+    pub fn status(&self, chat: Vec<ChatMessage>) -> LobbyStatus {
         LobbyStatus {
             players: self.players.clone(),
+            host: self.host,
+            seats: self.rules.players,
+            ready: self
+                .players
+                .iter()
+                .map(|player| player.id)
+                .filter(|id| self.ready.contains(id))
+                .collect(),
+            countdown: self.ready_since.map(|since| {
+                self.start_countdown
+                    .saturating_sub(since.elapsed())
+                    .as_secs_f32()
+                    .ceil() as u64
+            }),
+            chat,
         }
     }
 
-    /// Adds new player to the game.
+    /// Returns player that manages the lobby.
+    pub fn host(&self) -> Option<PlayerId> {
+        self.host
+    }
+
+    /// Returns all players in the lobby.
+    pub fn players(&self) -> &[PlayerData] {
+        &self.players
+    }
+
+    /// Adds new player to the game at the first free seat.
     ///
+    /// The first player becomes the host.
     /// Returns `None` if game is already full.
     /// Otherwise, returns [PlayerId] of the new player.
     pub fn add_player(&mut self, name: String) -> Option<PlayerId> {
-        if self.players.len() > 1 {
+        if self.is_full() {
             return None;
         }
-        let id = PlayerId::new(self.players.len() as u8);
-        self.players.push(PlayerData { id, name });
+        let seat = (0..self.rules.players).find(|seat| self.player_at(*seat).is_none())?;
+        let id = PlayerId::new(self.next_id);
+        self.next_id = self.next_id.checked_add(1)?;
+        self.players.push(PlayerData { id, name, seat });
+        self.players.sort_by_key(|player| player.seat);
+        self.host.get_or_insert(id);
+        self.update_ready_since();
         Some(id)
     }
 
-    /// Removes player from the game.
+    /// Removes player from the game, other players keep their seats.
     ///
+    /// If the host leaves, the next player in seat order becomes the host.
     /// Returns `true` if removed successfully.
     pub fn remove_player(&mut self, player_id: PlayerId) -> bool {
-        // TODO: check if host leaves
         let Some(index) = self.players.iter().position(|p| p.id == player_id) else {
             return false;
         };
         self.players.remove(index);
+        self.ready.remove(&player_id);
+        self.update_ready_since();
+        if self.host == Some(player_id) {
+            self.host = match self.players.len() {
+                0 => None,
+                len => Some(self.players[index % len].id),
+            };
+        }
         true
     }
 
-    /// Returns `true` if the game may be started by the host.
-    pub fn can_start(&self) -> bool {
-        // TODO: allow more than two players.
-        self.players.len() == 2
+    /// Swaps players at provided seats.
+    ///
+    /// If one of the seats is free, the other player moves to it.
+    pub fn swap_seats(&mut self, first: u8, second: u8) -> Result<(), ApiError> {
+        if let Some(seat) = [first, second]
+            .into_iter()
+            .find(|seat| *seat >= self.rules.players)
+        {
+            return Err(ApiError::BadRequest(format!("there is no seat #{seat}")));
+        }
+        for player in self.players.iter_mut() {
+            if player.seat == first {
+                player.seat = second;
+            } else if player.seat == second {
+                player.seat = first;
+            }
+        }
+        self.players.sort_by_key(|player| player.seat);
+        Ok(())
+    }
+
+    /// Toggles readiness of the player.
+    ///
+    /// Returns `true` if the player is ready now.
+    pub fn toggle_ready(&mut self, player: PlayerId) -> Result<bool, AuthFailed> {
+        if !self.players.iter().any(|data| data.id == player) {
+            return Err(AuthFailed::NotAPlayer);
+        }
+        let ready = !self.ready.remove(&player);
+        if ready {
+            self.ready.insert(player);
+        }
+        self.update_ready_since();
+        Ok(ready)
+    }
+
+    /// Starts the countdown once every seat is taken by ready player, stops it otherwise.
+    fn update_ready_since(&mut self) {
+        let all_ready = self.is_full()
+            && self
+                .players
+                .iter()
+                .all(|player| self.ready.contains(&player.id));
+        if !all_ready {
+            self.ready_since = None;
+        } else if self.ready_since.is_none() {
+            self.ready_since = Some(Instant::now());
+        }
+    }
+
+    /// Returns player sitting at provided seat.
+    fn player_at(&self, seat: u8) -> Option<&PlayerData> {
+        self.players.iter().find(|player| player.seat == seat)
+    }
+
+    /// Returns `true` if every player is ready and the countdown is over.
+    pub fn everyone_ready(&self) -> bool {
+        self.ready_since
+            .is_some_and(|since| since.elapsed() >= self.start_countdown)
     }
 
     /// Transforms state to the [RoundState].
-    pub fn to_started(&self) -> RoundState {
-        RoundState::new(self.players.clone())
+    pub fn to_started(&self, rng: &mut impl Rng) -> RoundState {
+        let turn_time = self
+            .rules
+            .turn_time
+            .map(|secs| Duration::from_secs(secs.into()));
+        RoundState::new(self.players.clone(), turn_time, rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use durak_lib::{errors::AuthFailed, game::rules::Rules, identifiers::PlayerId};
+
+    use super::LobbyState;
+
+    #[test]
+    fn test_host_passes_to_next_player() {
+        let mut lobby = LobbyState::new(Rules::default(), Duration::ZERO);
+        let host = lobby.add_player(String::from("host")).unwrap();
+        let guest = lobby.add_player(String::from("guest")).unwrap();
+        assert_eq!(lobby.host(), Some(host));
+
+        assert!(lobby.remove_player(guest));
+        assert_eq!(lobby.host(), Some(host), "host shouldn't change");
+        let guest = lobby.add_player(String::from("guest")).unwrap();
+        lobby.swap_seats(0, 1).unwrap();
+        assert!(lobby.remove_player(host));
+        assert_eq!(lobby.host(), Some(guest));
+        assert!(lobby.remove_player(guest));
+        assert_eq!(lobby.host(), None);
+        assert!(!lobby.remove_player(PlayerId::new(7)));
+    }
+
+    #[test]
+    fn test_seats_are_kept() {
+        let mut lobby = LobbyState::new(
+            Rules {
+                players: 3,
+                ..Rules::default()
+            },
+            Duration::ZERO,
+        );
+        let first = lobby.add_player(String::from("first")).unwrap();
+        let second = lobby.add_player(String::from("second")).unwrap();
+        assert!(lobby.remove_player(first));
+        let third = lobby.add_player(String::from("third")).unwrap();
+        assert_ne!(third, first, "ids should never be reused");
+
+        let seats: Vec<_> = lobby.players().iter().map(|p| (p.id, p.seat)).collect();
+        assert_eq!(seats, [(third, 0), (second, 1)]);
+
+        lobby.swap_seats(0, 2).unwrap();
+        let seats: Vec<_> = lobby.players().iter().map(|p| (p.id, p.seat)).collect();
+        assert_eq!(seats, [(second, 1), (third, 2)]);
+        assert!(lobby.swap_seats(1, 3).is_err());
+    }
+
+    #[test]
+    fn test_everyone_must_be_ready() {
+        let mut lobby = LobbyState::new(Rules::default(), Duration::ZERO);
+        let host = lobby.add_player(String::from("host")).unwrap();
+        assert!(lobby.toggle_ready(host).unwrap());
+        assert!(!lobby.everyone_ready(), "lobby isn't full");
+
+        let guest = lobby.add_player(String::from("guest")).unwrap();
+        assert!(!lobby.everyone_ready(), "new player isn't ready");
+        assert!(lobby.toggle_ready(guest).unwrap());
+        assert!(lobby.everyone_ready());
+        assert!(!lobby.toggle_ready(host).unwrap());
+        assert!(!lobby.everyone_ready());
+
+        assert!(lobby.remove_player(guest));
+        assert!(matches!(
+            lobby.toggle_ready(guest),
+            Err(AuthFailed::NotAPlayer)
+        ));
+
+        let mut lobby = LobbyState::new(Rules::default(), Duration::from_secs(60));
+        for name in ["host", "guest"] {
+            let player = lobby.add_player(String::from(name)).unwrap();
+            lobby.toggle_ready(player).unwrap();
+        }
+        assert!(!lobby.everyone_ready(), "countdown isn't over");
+        assert_eq!(lobby.status(Vec::new()).countdown, Some(60));
     }
 }
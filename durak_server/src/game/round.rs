@@ -1,7 +1,11 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use durak_lib::{
-    errors::PlayCardError,
+    chat::ChatMessage,
+    errors::{ActionError, PlayCardError},
     game::{
         card::{Card, CardRank, CardSuit},
         deck::Deck,
@@ -10,56 +14,69 @@ use durak_lib::{
         table::Table,
     },
     identifiers::PlayerId,
-    status::{round::RoundStatus, PlayerData},
+    status::{
+        round::{RoundStatus, SpectatorRoundStatus},
+        PlayerData,
+    },
 };
+use rand::Rng;
+use serde::Serialize;
+use tracing::error;
 
 use super::CardPlayedOutcome;
 
 /// State of started game.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct RoundState {
     pub deck: Deck,
     pub trump: Card,
     pub table: Table,
     pub attacker: PlayerId,
     pub defender: PlayerId,
+    /// Players ordered by their seats.
     pub players: Vec<PlayerData>,
     pub hands: HashMap<PlayerId, Hand>,
+    /// Cards that were beaten off.
+    pub discard: Vec<Card>,
+    /// Time the player on turn has to act.
+    pub turn_time: Option<Duration>,
+    /// Time of the last action in the round.
+    #[serde(skip)]
+    turn_started: Instant,
 }
 
 /// New round creation.
 impl RoundState {
-    pub fn new(players: Vec<PlayerData>) -> Self {
-        let mut deck = Self::create_deck();
+    pub fn new(players: Vec<PlayerData>, turn_time: Option<Duration>, rng: &mut impl Rng) -> Self {
+        let mut deck = Self::create_deck(rng);
         let hands = Self::create_hands(&mut deck, players.iter().map(|p| p.id).collect());
         let trump = Self::pick_trump(&mut deck);
-        let attacker = Self::define_first_attacker(&hands, trump.suit);
-        // TODO: allow more than two players
-        let defender = if attacker == PlayerId::new(0) {
-            PlayerId::new(1)
-        } else {
-            PlayerId::new(0)
-        };
+        let attacker = Self::define_first_attacker(&players, &hands, trump.suit);
 
-        Self {
+        let mut round = Self {
             deck,
             trump,
             table: Table::new(),
             attacker,
-            defender,
+            defender: attacker,
             hands,
             players,
-        }
+            discard: Vec::new(),
+            turn_time,
+            turn_started: Instant::now(),
+        };
+        round.defender = round.next_player(attacker);
+        round
     }
 
     /// Creates new shuffled deck.
-    fn create_deck() -> Deck {
+    fn create_deck(rng: &mut impl Rng) -> Deck {
         let mut deck = Deck::new();
-        deck.shuffle();
+        deck.shuffle_with(rng);
         deck
     }
 
-    /// Creates new hands and deals cards.
+    /// Creates new hands and deals cards in order of players.
     fn create_hands(deck: &mut Deck, players: Vec<PlayerId>) -> HashMap<PlayerId, Hand> {
         players
             .into_iter()
@@ -88,13 +105,18 @@ impl RoundState {
     /// Defines first attacker for the round.
     ///
     /// The initial attacker is the player possessing the smallest trump card.
+    /// If no one has trumps, the player at the first seat attacks.
     // TODO: announce smallest trump holder to other players.
-    fn define_first_attacker(hands: &HashMap<PlayerId, Hand>, trump: CardSuit) -> PlayerId {
-        let mut min = (PlayerId::new(0), CardRank::Ace);
-        for (player_id, hand) in hands.iter() {
-            for card in hand.iter() {
+    fn define_first_attacker(
+        players: &[PlayerData],
+        hands: &HashMap<PlayerId, Hand>,
+        trump: CardSuit,
+    ) -> PlayerId {
+        let mut min = (players[0].id, CardRank::Ace);
+        for player in players {
+            for card in hands[&player.id].iter() {
                 if card.suit == trump && card.rank <= min.1 {
-                    min = (*player_id, card.rank);
+                    min = (player.id, card.rank);
                 }
             }
         }
@@ -110,7 +132,8 @@ impl RoundState {
         player_id: PlayerId,
         card: Card,
     ) -> Result<CardPlayedOutcome, PlayCardError> {
-        let Some((&player_id, hand)) = self.hands.iter_mut().find(|(id, _)| **id == player_id) else {
+        let Some((&player_id, hand)) = self.hands.iter_mut().find(|(id, _)| **id == player_id)
+        else {
             panic!("Authenticated player not found");
         };
 
@@ -125,6 +148,7 @@ impl RoundState {
                 self.table.defend(card, self.trump.suit)?;
             }
             hand.remove(card);
+            self.turn_started = Instant::now();
 
             if let Some(winner) = self.check_winner() {
                 return Ok(CardPlayedOutcome::Win(winner));
@@ -136,43 +160,46 @@ impl RoundState {
     }
 
     /// Attacker decided to stop an attack.
-    pub fn retreat(&mut self, player_id: PlayerId) -> bool {
+    pub fn retreat(&mut self, player_id: PlayerId) -> Result<(), ActionError> {
         if self.attacker != player_id {
-            return false;
-        }
-        if self.table.retreat() {
-            self.swap_players();
-            self.deal_cards();
-            true
-        } else {
-            false
+            return Err(ActionError::InvalidTurn);
         }
+        let cards = self.table.retreat().ok_or(ActionError::CantRetreat)?;
+        self.discard.extend(cards);
+        self.deal_cards();
+        self.pass_turn(self.defender);
+        self.turn_started = Instant::now();
+        Ok(())
     }
 
     /// Defender decided to take all cards from the table.
-    pub fn take(&mut self, player_id: PlayerId) -> bool {
-        let Some((&player_id, hand)) = self.hands.iter_mut().find(|(id, _)| **id == player_id) else {
-            return false;
+    pub fn take(&mut self, player_id: PlayerId) -> Result<(), ActionError> {
+        let Some((&player_id, hand)) = self.hands.iter_mut().find(|(id, _)| **id == player_id)
+        else {
+            return Err(ActionError::InvalidTurn);
         };
 
         if self.defender != player_id {
-            return false;
+            return Err(ActionError::InvalidTurn);
         }
         let Some(cards) = self.table.take() else {
-            return false;
+            return Err(ActionError::CantTake);
         };
         for card in cards {
             hand.add(card);
         }
         self.deal_cards();
-        true
+        self.pass_turn(self.next_player(self.defender));
+        self.turn_started = Instant::now();
+        Ok(())
     }
 
     /// Generates status report for specific player.
-    pub fn status(&self, player: PlayerId) -> RoundStatus {
+    pub fn status(&self, player: PlayerId, chat: Vec<ChatMessage>) -> RoundStatus {
         RoundStatus {
             trump: self.trump,
             turn: self.turn(),
+            turn_time_left: self.time_left().map(as_secs_ceil),
             attacker: self.attacker,
             defender: self.defender,
             table: self.table.clone(),
@@ -182,15 +209,68 @@ impl RoundState {
                 .iter()
                 .filter(|p| p.id != player)
                 .cloned()
-                .map(|PlayerData { id, name }| Opponent {
+                .map(|PlayerData { id, name, .. }| Opponent {
                     id,
                     name,
                     cards_number: self.hands.get(&id).unwrap().count() as u8,
                 })
                 .collect(),
             hand: self.hands.get(&player).unwrap().clone(),
+            chat,
+        }
+    }
+
+    /// Generates status report for spectators.
+    pub fn spectator_status(&self, chat: Vec<ChatMessage>) -> SpectatorRoundStatus {
+        SpectatorRoundStatus {
+            trump: self.trump,
+            turn: self.turn(),
+            turn_time_left: self.time_left().map(as_secs_ceil),
+            attacker: self.attacker,
+            defender: self.defender,
+            table: self.table.clone(),
+            deck_size: self.deck.count() as u8,
+            discard: self.discard.clone(),
+            players: self
+                .players
+                .iter()
+                .map(|PlayerData { id, name, .. }| Opponent {
+                    id: *id,
+                    name: name.clone(),
+                    cards_number: self.hands[id].count() as u8,
+                })
+                .collect(),
+            chat,
         }
     }
+
+    /// Acts on behalf of the player on turn if their time is over.
+    ///
+    /// Defender takes cards from the table. Attacker stops the attack,
+    /// or passes the turn to the defender if the attack hasn't started yet.
+    /// Returns `true` if the turn timed out.
+    pub fn apply_timeout(&mut self) -> bool {
+        if self.time_left() != Some(Duration::ZERO) {
+            return false;
+        }
+        let player = self.turn();
+        let result = if player == self.defender {
+            self.take(player)
+        } else if self.table.can_retreat() {
+            self.retreat(player)
+        } else {
+            self.pass_turn(self.defender);
+            self.turn_started = Instant::now();
+            Ok(())
+        };
+        if let Err(err) = result {
+            // Timer restarts, so the same failure isn't logged on every check.
+            error!("player #{player} on turn failed to act after timeout: {err}");
+            self.turn_started = Instant::now();
+            return false;
+        }
+        true
+    }
 }
 
 /// Round utility.
@@ -213,8 +293,15 @@ impl RoundState {
     /// Deals cards to players.
     ///
     /// Players holding fewer than 6 cards receive additional cards to bring their total back to 6.
+    /// Cards are dealt in seat order starting with the attacker, the defender draws last.
     fn deal_cards(&mut self) {
-        for player in self.iter_players(self.attacker) {
+        let defender = self.defender;
+        let players: Vec<_> = self
+            .iter_players(self.attacker)
+            .filter(|player| *player != defender)
+            .chain(std::iter::once(defender))
+            .collect();
+        for player in players {
             let hand = self.hands.get_mut(&player).unwrap();
             let additional_cards_number = 6usize.saturating_sub(hand.count());
             for _ in 0..additional_cards_number {
@@ -226,23 +313,46 @@ impl RoundState {
         }
     }
 
-    /// Returns mutable iterator of player ids starting with provided.
-    fn iter_players(&mut self, start: PlayerId) -> impl Iterator<Item = PlayerId> {
+    /// Returns iterator of player ids in seat order, starting with provided.
+    fn iter_players(&self, start: PlayerId) -> impl Iterator<Item = PlayerId> {
         let start = self
-            .hands
+            .players
             .iter()
-            .position(|(player_id, _)| *player_id == start)
+            .position(|player| player.id == start)
             .unwrap();
-        let count = self.hands.len();
-        (start..count)
-            .chain(0..start)
-            .map(|i| PlayerId::new(i as u8))
+        let players: Vec<_> = self.players.iter().map(|player| player.id).collect();
+        let (before, after) = players.split_at(start);
+        let mut ordered = after.to_vec();
+        ordered.extend_from_slice(before);
+        ordered.into_iter()
     }
 
-    /// Swaps attacker and defender.
-    // TODO: allow more than two players.
-    fn swap_players(&mut self) {
-        std::mem::swap(&mut self.attacker, &mut self.defender)
+    /// Returns id of the player sitting after provided one.
+    fn next_player(&self, player: PlayerId) -> PlayerId {
+        self.iter_players(player)
+            .nth(1)
+            .expect("round should have at least two players")
+    }
+
+    /// Makes provided player the attacker and the next one the defender.
+    fn pass_turn(&mut self, attacker: PlayerId) {
+        self.attacker = attacker;
+        self.defender = self.next_player(attacker);
+    }
+
+    /// Returns id of the player with the least cards in hand.
+    pub fn leader(&self) -> PlayerId {
+        self.players
+            .iter()
+            .map(|player| player.id)
+            .min_by_key(|id| self.hands.get(id).map(|hand| hand.count()).unwrap_or(0))
+            .expect("round should have players")
+    }
+
+    /// Returns time left for the player on turn to act.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.turn_time
+            .map(|time| time.saturating_sub(self.turn_started.elapsed()))
     }
 
     /// Returns id of the player whose turn is it to play.
@@ -254,3 +364,50 @@ impl RoundState {
         }
     }
 }
+
+/// Rounds duration up to whole seconds, so zero is shown only when time is over.
+fn as_secs_ceil(duration: Duration) -> u64 {
+    duration.as_secs_f32().ceil() as u64
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use durak_lib::{identifiers::PlayerId, status::PlayerData};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::RoundState;
+
+    fn players(count: u8) -> Vec<PlayerData> {
+        (0..count)
+            .map(|i| PlayerData {
+                id: PlayerId::new(i),
+                name: i.to_string(),
+                seat: i,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_turn_timeout() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut round = RoundState::new(players(2), Some(Duration::ZERO), &mut rng);
+        let attacker = round.attacker;
+        let defender = round.defender;
+        let card = round.hands[&attacker].iter().next().unwrap();
+        round.play_card(attacker, card).unwrap();
+
+        assert!(round.apply_timeout(), "defender should take");
+        assert!(round.table.is_empty());
+        assert_eq!(round.hands[&defender].count(), 7);
+        assert_eq!(round.attacker, attacker);
+
+        assert!(round.apply_timeout(), "attacker should pass");
+        assert_eq!(round.attacker, defender);
+
+        let mut round = RoundState::new(players(2), None, &mut rng);
+        assert!(!round.apply_timeout());
+        assert_eq!(round.time_left(), None);
+    }
+}
@@ -0,0 +1,673 @@
+//! Server of the Durak card game.
+//!
+//! [router] creates all endpoints of the server, so it may be hosted in-process.
+//! Binary only resolves [config::Config] and binds the listener.
+
+pub mod admin;
+pub mod auth;
+pub mod config;
+pub mod extract;
+pub mod game;
+pub mod matchmaking;
+pub mod metrics;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod rate_limit;
+pub mod state;
+
+use auth::{Authenticate, AuthenticateHost, AuthenticateSpectator};
+#[cfg(feature = "openapi")]
+use durak_lib::errors::ErrorResponse;
+use durak_lib::{
+    chat::{ChatMessage, PostMessageData},
+    errors::{AccessError, ApiError, AuthFailed, InvalidPhase},
+    game::card::Card,
+    identifiers::PlayerId,
+    network::{
+        CreateGameData, CreateGameResponse, JoinGameData, JoinGameResponse, KickQuery,
+        LobbySummary, SpectateData, SpectateResponse, SwapSeatsQuery, VersionResponse,
+        PROTOCOL_VERSION,
+    },
+    status::{SpectatorStatus, StatusResponse},
+};
+
+use axum::{
+    extract::State,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use extract::{Json, Query};
+use game::{CardPlayedOutcome, GamePhase};
+use metrics::{track_requests, Metrics};
+use rate_limit::{limit_by_ip, limit_join_attempts, RateLimiter};
+use state::{Auth, Games};
+use tracing::info;
+
+use crate::{config::Config, state::AppState};
+
+/// Creates router with all endpoints of the server.
+///
+/// Router expects [axum::extract::ConnectInfo] with [std::net::SocketAddr] of the client,
+/// see [Router::into_make_service_with_connect_info].
+///
+/// Task that removes expired games is spawned as well, so it must be called within Tokio runtime.
+pub fn router(config: &Config) -> Router {
+    let state = AppState::new(config);
+    state.spawn_expiry_task(config.expiry);
+    routes(state)
+}
+
+/// Creates router with all endpoints, sharing provided state.
+pub fn routes(state: AppState) -> Router {
+    let router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics::metrics))
+        .route("/version", get(version))
+        .route(
+            "/lobbies",
+            get(lobbies).layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route(
+            "/create",
+            post(create_game).layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route(
+            "/join",
+            post(join_game)
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    limit_join_attempts,
+                ))
+                .layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route(
+            "/queue",
+            post(matchmaking::enter_queue)
+                .layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route(
+            "/spectate",
+            post(spectate)
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    limit_join_attempts,
+                ))
+                .layer(middleware::from_fn_with_state(state.clone(), limit_by_ip)),
+        )
+        .route("/spectate/status", get(spectator_status))
+        .route("/queue/status", get(matchmaking::queue_status))
+        .route("/queue/leave", post(matchmaking::leave_queue))
+        .route("/game/ready", post(toggle_ready))
+        .route("/game/start", post(start))
+        .route("/game/status", get(status))
+        .route("/game/play", post(play_card))
+        .route("/game/take", post(take))
+        .route("/game/retreat", post(retreat))
+        .route("/game/leave", post(leave))
+        .route("/game/kick", post(kick))
+        .route("/game/seats/swap", post(swap_seats))
+        .route("/game/chat", post(post_message))
+        .route("/admin/games", get(admin::list_games))
+        .route("/admin/game", get(admin::inspect_game))
+        .route("/admin/finish", post(admin::finish_game))
+        .route("/admin/kick", post(admin::kick_player))
+        .route("/admin/delete", post(admin::delete_game));
+    #[cfg(feature = "openapi")]
+    let router = router.route("/openapi.json", get(openapi::openapi));
+
+    router
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_requests,
+        ))
+        .fallback(not_found)
+        .with_state(state)
+}
+
+async fn not_found() -> ApiError {
+    ApiError::NotFound
+}
+
+/// Reports that the server is alive.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/healthz",
+    responses((status = 200, description = "Server is alive", body = ())),
+))]
+async fn healthz() -> Json<()> {
+    Json(())
+}
+
+/// Reports whether the server accepts new games.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Server accepts new games", body = ()),
+        (status = 503, description = "Server hosts too many games", body = ErrorResponse),
+    ),
+))]
+async fn readyz(State(games): State<Games>) -> Result<Json<()>, ApiError> {
+    if games.is_full() {
+        return Err(ApiError::TooManyGames);
+    }
+    Ok(Json(()))
+}
+
+/// Returns version of the server and protocol it speaks.
+///
+/// Clients should check it before sending any other request.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/version",
+    responses((status = 200, description = "Version of the server", body = VersionResponse)),
+))]
+async fn version() -> VersionResponse {
+    VersionResponse {
+        protocol: PROTOCOL_VERSION,
+        server: env!("CARGO_PKG_VERSION").to_owned(),
+    }
+}
+
+/// Lists public lobbies that may be joined.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/lobbies",
+    responses(
+        (status = 200, description = "Public lobbies", body = Vec<LobbySummary>),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+))]
+async fn lobbies(State(games): State<Games>) -> Json<Vec<LobbySummary>> {
+    Json(games.lobbies())
+}
+
+/// Creates new game.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/create",
+    request_body = CreateGameData,
+    responses(
+        (status = 200, description = "Game created", body = CreateGameResponse),
+        (status = 400, description = "Malformed request", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 503, description = "Server hosts too many games", body = ErrorResponse),
+    ),
+))]
+async fn create_game(
+    State(auth): State<Auth>,
+    State(games): State<Games>,
+    State(metrics): State<Metrics>,
+    Json(data): Json<CreateGameData>,
+) -> Result<CreateGameResponse, ApiError> {
+    let game_id = games.create(&data)?;
+    metrics.games_created.inc();
+    let player_id = PlayerId::new(0);
+    let token = auth.generate_token(game_id, games.nonce(game_id)?, player_id);
+    let has_password = !data.password.is_empty();
+    auth.store_password(game_id, data.password).await;
+    // Game can't be joined before its password is stored.
+    if data.public {
+        games.with_lobby_game(game_id, |lobby| lobby.publish(has_password))?;
+    }
+    info!("created game `{game_id}`");
+
+    Ok(CreateGameResponse::Ok {
+        game_id,
+        player_id,
+        token,
+    })
+}
+
+/// Joins already created game.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/join",
+    request_body = JoinGameData,
+    responses(
+        (status = 200, description = "Joined the game", body = JoinGameResponse),
+        (status = 400, description = "Wrong password, full or started game", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+))]
+async fn join_game(
+    State(auth): State<Auth>,
+    State(games): State<Games>,
+    State(metrics): State<Metrics>,
+    Json(data): Json<JoinGameData>,
+) -> Result<JoinGameResponse, ApiError> {
+    games.validate_name(&data.name)?;
+    match auth.validate_password(data.id, data.password).await {
+        Ok(true) => {}
+        Ok(false) => {
+            info!("attempted to join with wrong password `{}`", data.id);
+            metrics.auth_failed(&ApiError::InvalidPassword);
+            return Err(ApiError::InvalidPassword);
+        }
+        Err(not_found) => {
+            info!("attempted to join nonexisting game `{}`", data.id);
+            return Err(not_found.into());
+        }
+    }
+
+    let nonce = games.nonce(data.id)?;
+    games
+        .with_lobby_game(data.id, |lobby| match lobby.add_player(data.name) {
+            Some(player_id) => {
+                info!("player joined game `{}`", data.id);
+                metrics.games_joined.inc();
+                let token = auth.generate_token(data.id, nonce, player_id);
+                Ok(JoinGameResponse::Ok {
+                    game_id: data.id,
+                    player_id,
+                    token,
+                })
+            }
+            None => {
+                info!("attempted to join full game `{}`", data.id);
+                Err(ApiError::TooManyPlayers)
+            }
+        })
+        .map_err(|err| {
+            match err {
+                AccessError::AuthFailed(_) => {
+                    unreachable!("token auth shouldn't fail for game join request");
+                }
+                AccessError::GameNotFound(_) => {
+                    info!("attempted to join nonexisting game `{}`", data.id);
+                }
+                AccessError::InvalidPhase(_) => {
+                    info!("attempted to join started game `{}`", data.id);
+                }
+            }
+            ApiError::from(err)
+        })?
+}
+
+/// Watches the game without taking a seat.
+///
+/// Returned token only allows to request spectator status.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/spectate",
+    request_body = SpectateData,
+    responses(
+        (status = 200, description = "Watching the game", body = SpectateResponse),
+        (status = 400, description = "Wrong password", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+    ),
+))]
+async fn spectate(
+    State(auth): State<Auth>,
+    State(games): State<Games>,
+    State(metrics): State<Metrics>,
+    Json(data): Json<SpectateData>,
+) -> Result<SpectateResponse, ApiError> {
+    if !auth.validate_password(data.id, data.password).await? {
+        info!("attempted to spectate with wrong password `{}`", data.id);
+        metrics.auth_failed(&ApiError::InvalidPassword);
+        return Err(ApiError::InvalidPassword);
+    }
+    // Game may be removed while password is checked.
+    let nonce = games.nonce(data.id)?;
+    info!("spectator joined game `{}`", data.id);
+
+    Ok(SpectateResponse::Ok {
+        game_id: data.id,
+        token: auth.generate_spectator_token(data.id, nonce),
+    })
+}
+
+/// Requests status of the game without any hands.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/spectate/status",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Status of the game", body = SpectatorStatus),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn spectator_status(
+    State(games): State<Games>,
+    AuthenticateSpectator(game_id): AuthenticateSpectator,
+) -> Result<SpectatorStatus, ApiError> {
+    Ok(games.with_game(game_id, |game| game.spectator_status())?)
+}
+
+/// Toggles whether the player is ready to start the game.
+///
+/// Returns `true` if the player is ready now.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/ready",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Readiness toggled", body = bool),
+        (status = 400, description = "Game is already started", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn toggle_ready(
+    State(games): State<Games>,
+    Authenticate(player): Authenticate,
+) -> Result<Json<bool>, ApiError> {
+    let ready =
+        games.with_lobby_game(player.game_id, |lobby| lobby.toggle_ready(player.player_id))??;
+    info!(
+        "Player #{} is {} in game `{}`",
+        player.player_id,
+        if ready { "ready" } else { "not ready" },
+        player.game_id
+    );
+    Ok(Json(ready))
+}
+
+/// Starts the game.
+///
+/// Should be called by game host once every player is ready.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/start",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Game started", body = ()),
+        (status = 400, description = "Game can't be started", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 403, description = "Player is not a host", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn start(
+    State(games): State<Games>,
+    AuthenticateHost(player): AuthenticateHost,
+) -> Result<Json<()>, ApiError> {
+    games.with_game(player.game_id, |game| game.start(&mut games.deck_rng()))??;
+    info!("Started game `{}`", player.game_id);
+    Ok(Json(()))
+}
+
+/// Requests information about [StatusResponse] for the current player.
+///
+/// Should be called regularly during the game unless it is player's turn.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/game/status",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Status of the game", body = StatusResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn status(
+    State(games): State<Games>,
+    Authenticate(auth): Authenticate,
+) -> Result<StatusResponse, ApiError> {
+    Ok(games.with_game(auth.game_id, |game| game.status(auth.player_id))?)
+}
+
+/// Plays specified card on the table.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/play",
+    params(Card),
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Card played", body = ()),
+        (status = 400, description = "Card can't be played", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn play_card(
+    Query(card): Query<Card>,
+    State(games): State<Games>,
+    State(metrics): State<Metrics>,
+    Authenticate(player): Authenticate,
+) -> Result<Json<()>, ApiError> {
+    games.with_game(player.game_id, |game| {
+        let round = game.round_state()?;
+        match round.play_card(player.player_id, card)? {
+            CardPlayedOutcome::None => {
+                info!(
+                    "card played by player #{} in game `{}`",
+                    player.player_id, player.game_id
+                );
+            }
+            CardPlayedOutcome::Win(winner) => {
+                info!(
+                    "card played by player #{} in game `{}` caused win",
+                    player.player_id, player.game_id
+                );
+                game.finish(winner)?;
+                metrics.games_finished.inc();
+            }
+        }
+        Ok::<_, ApiError>(())
+    })??;
+    Ok(Json(()))
+}
+
+/// Takes all cards from the table into player's hand.
+///
+/// Should be called by defending player.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/take",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Cards taken", body = ()),
+        (status = 400, description = "Cards can't be taken", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn take(
+    State(games): State<Games>,
+    Authenticate(player): Authenticate,
+) -> Result<Json<()>, ApiError> {
+    games.with_started_game(player.game_id, |round| round.take(player.player_id))??;
+    info!(
+        "Cards are taken by player #{} in game `{}`",
+        player.player_id, player.game_id
+    );
+    Ok(Json(()))
+}
+
+/// Discards all cards at the table.
+///
+/// Should be called by attacking player.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/retreat",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Attack stopped", body = ()),
+        (status = 400, description = "Attack can't be stopped", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn retreat(
+    State(games): State<Games>,
+    Authenticate(player): Authenticate,
+) -> Result<Json<()>, ApiError> {
+    games.with_started_game(player.game_id, |round| round.retreat(player.player_id))??;
+    info!(
+        "Player #{} retreated in game `{}`",
+        player.player_id, player.game_id
+    );
+    Ok(Json(()))
+}
+
+/// Removes player from the lobby.
+///
+/// Tokens of the removed player are invalidated.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/kick",
+    params(KickQuery),
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Player removed", body = ()),
+        (status = 400, description = "Game is already started or player can't be removed", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 403, description = "Not a host", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn kick(
+    Query(query): Query<KickQuery>,
+    State(games): State<Games>,
+    State(auth): State<Auth>,
+    AuthenticateHost(host): AuthenticateHost,
+) -> Result<Json<()>, ApiError> {
+    if query.player == host.player_id {
+        return Err(ApiError::BadRequest(String::from(
+            "host can't remove themselves, leave the game instead",
+        )));
+    }
+    games.with_lobby_game(host.game_id, |lobby| {
+        if lobby.remove_player(query.player) {
+            Ok(())
+        } else {
+            Err(ApiError::BadRequest(format!(
+                "player #{} is not in the game",
+                query.player
+            )))
+        }
+    })??;
+    auth.revoke_tokens(host.game_id, query.player);
+    info!(
+        "host kicked player #{} from game `{}`",
+        query.player, host.game_id
+    );
+    Ok(Json(()))
+}
+
+/// Swaps players at provided seats of the lobby.
+///
+/// If one of the seats is empty, the other player moves to it.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/seats/swap",
+    params(SwapSeatsQuery),
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Seats swapped", body = ()),
+        (status = 400, description = "Game is already started or seat doesn't exist", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 403, description = "Not a host", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn swap_seats(
+    Query(query): Query<SwapSeatsQuery>,
+    State(games): State<Games>,
+    AuthenticateHost(host): AuthenticateHost,
+) -> Result<Json<()>, ApiError> {
+    games.with_lobby_game(host.game_id, |lobby| {
+        lobby.swap_seats(query.first, query.second)
+    })??;
+    info!(
+        "host swapped seats #{} and #{} in game `{}`",
+        query.first, query.second, host.game_id
+    );
+    Ok(Json(()))
+}
+
+/// Posts message to the chat of the game.
+///
+/// Messages are delivered to other players with the status of the game.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/chat",
+    request_body = PostMessageData,
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Message posted", body = ChatMessage),
+        (status = 400, description = "Message is empty or too long", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+        (status = 429, description = "Too many messages", body = ErrorResponse),
+    ),
+))]
+async fn post_message(
+    State(games): State<Games>,
+    State(limiter): State<RateLimiter>,
+    Authenticate(player): Authenticate,
+    Json(data): Json<PostMessageData>,
+) -> Result<Json<ChatMessage>, ApiError> {
+    let text = data.text.trim();
+    games.validate_message(text)?;
+    limiter.hit_chat(player.game_id, player.player_id)?;
+    let message = games.with_game(player.game_id, |game| {
+        // Player may have left the lobby with the token still valid.
+        let author = game
+            .players()
+            .iter()
+            .find(|p| p.id == player.player_id)
+            .cloned()
+            .ok_or(AuthFailed::TokenDoesntMatch)?;
+        Ok::<_, ApiError>(game.chat.post(&author, text.to_owned()).clone())
+    })??;
+    info!(
+        "player #{} posted message in game `{}`",
+        player.player_id, player.game_id
+    );
+    Ok(Json(message))
+}
+
+/// Leave the game.
+///
+/// Leaving ongoing game is a forfeit: in two-player game the other player wins,
+/// otherwise the game goes on without the leaver and their cards are discarded.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/game/leave",
+    security(("token" = [])),
+    responses(
+        (status = 200, description = "Left the game", body = ()),
+        (status = 400, description = "Game is already finished", body = ErrorResponse),
+        (status = 401, description = "Authorization failed", body = ErrorResponse),
+        (status = 404, description = "Game not found", body = ErrorResponse),
+    ),
+))]
+async fn leave(
+    State(games): State<Games>,
+    State(auth): State<Auth>,
+    State(metrics): State<Metrics>,
+    Authenticate(player): Authenticate,
+) -> Result<Json<()>, ApiError> {
+    games.with_game(player.game_id, |game| {
+        match &mut game.phase {
+            GamePhase::Lobby(lobby) => {
+                if lobby.remove_player(player.player_id) {
+                    auth.revoke_tokens(player.game_id, player.player_id);
+                    info!(
+                        "player #{} left the game `{}`",
+                        player.player_id, player.game_id
+                    );
+                }
+            }
+            GamePhase::Round(_) => {
+                let winner = game.forfeit(player.player_id)?;
+                metrics.games_finished.inc();
+                info!(
+                    "player #{} forfeited game `{}`, player #{winner} won",
+                    player.player_id, player.game_id
+                );
+            }
+            GamePhase::Finished(_) => return Err(InvalidPhase),
+        }
+        Ok(())
+    })??;
+    Ok(Json(()))
+}
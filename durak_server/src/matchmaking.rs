@@ -0,0 +1,311 @@
+//! Matchmaking queue for players that don't have an invite.
+//!
+//! Players are grouped by rules they want to play. As soon as a group has enough players,
+//! a game is created and started, and every player of the group receives its credentials
+//! on the next status request.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::extract::{FromRef, State};
+#[cfg(feature = "openapi")]
+use durak_lib::errors::ErrorResponse;
+use durak_lib::{
+    errors::ApiError,
+    game::rules::Rules,
+    matchmaking::{QueueData, QueueStatus, Ticket, TicketQuery},
+    network::{CreateGameData, JoinGameResponse},
+};
+use rand::{distributions::Alphanumeric, Rng};
+use tracing::info;
+
+use crate::{
+    extract::{Json, Query},
+    metrics::Metrics,
+    state::{AppState, Auth, Games},
+};
+
+/// Time after which players that don't poll their status leave the queue.
+const QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Length of generated tickets.
+const TICKET_LENGTH: usize = 32;
+
+/// Players waiting for the game.
+#[derive(Clone, Default)]
+pub struct Matchmaking {
+    queue: Arc<Mutex<Queue>>,
+}
+
+#[derive(Default)]
+struct Queue {
+    entries: HashMap<Ticket, Entry>,
+    /// Tickets of waiting players grouped by rules, in order of arrival.
+    groups: HashMap<Rules, Vec<Ticket>>,
+}
+
+struct Entry {
+    name: String,
+    rules: Rules,
+    /// Time of the last request with this ticket.
+    last_seen: Instant,
+    /// Credentials, once the game is created.
+    matched: Option<JoinGameResponse>,
+}
+
+impl Matchmaking {
+    /// Adds player to the queue.
+    ///
+    /// Creates the game if player completes the group.
+    pub fn enter(
+        &self,
+        data: QueueData,
+        games: &Games,
+        auth: &Auth,
+    ) -> Result<QueueStatus, ApiError> {
+        games.validate_name(&data.name)?;
+        games.validate_rules(&data.rules)?;
+        if games.is_full() {
+            return Err(ApiError::TooManyGames);
+        }
+
+        let mut queue = self.queue.lock().unwrap();
+        queue.remove_expired(Instant::now());
+        let ticket = loop {
+            let ticket: String = rand::thread_rng()
+                .sample_iter(Alphanumeric)
+                .take(TICKET_LENGTH)
+                .map(char::from)
+                .collect();
+            let ticket = Ticket::new(ticket);
+            if !queue.entries.contains_key(&ticket) {
+                break ticket;
+            }
+        };
+        let rules = data.rules.clone();
+        queue.entries.insert(
+            ticket.clone(),
+            Entry {
+                name: data.name,
+                rules: data.rules,
+                last_seen: Instant::now(),
+                matched: None,
+            },
+        );
+        queue
+            .groups
+            .entry(rules.clone())
+            .or_default()
+            .push(ticket.clone());
+
+        if let Err(err) = queue.try_match(&rules, games, auth) {
+            queue.remove(&ticket);
+            return Err(err);
+        }
+        queue.status(&ticket).ok_or(ApiError::NotQueued)
+    }
+
+    /// Returns status of the queued player and keeps it in the queue.
+    pub fn status(&self, ticket: &Ticket) -> Result<QueueStatus, ApiError> {
+        let mut queue = self.queue.lock().unwrap();
+        let entry = queue.entries.get_mut(ticket).ok_or(ApiError::NotQueued)?;
+        entry.last_seen = Instant::now();
+        queue.status(ticket).ok_or(ApiError::NotQueued)
+    }
+
+    /// Removes player from the queue.
+    pub fn leave(&self, ticket: &Ticket) -> Result<(), ApiError> {
+        let mut queue = self.queue.lock().unwrap();
+        queue.remove(ticket).ok_or(ApiError::NotQueued)?;
+        Ok(())
+    }
+
+    /// Removes players that stopped polling their status.
+    pub fn remove_expired(&self) {
+        self.queue.lock().unwrap().remove_expired(Instant::now());
+    }
+}
+
+impl Queue {
+    fn status(&self, ticket: &Ticket) -> Option<QueueStatus> {
+        let entry = self.entries.get(ticket)?;
+        if let Some(matched) = &entry.matched {
+            return Some(QueueStatus::Matched(matched.clone()));
+        }
+        let players = self.groups.get(&entry.rules).map_or(0, Vec::len);
+        Some(QueueStatus::Waiting {
+            ticket: ticket.clone(),
+            players: players as u8,
+        })
+    }
+
+    fn remove(&mut self, ticket: &Ticket) -> Option<Entry> {
+        let entry = self.entries.remove(ticket)?;
+        if let Some(group) = self.groups.get_mut(&entry.rules) {
+            group.retain(|queued| queued != ticket);
+            if group.is_empty() {
+                self.groups.remove(&entry.rules);
+            }
+        }
+        Some(entry)
+    }
+
+    fn remove_expired(&mut self, now: Instant) {
+        let expired: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.last_seen) > QUEUE_TIMEOUT)
+            .map(|(ticket, _)| ticket.clone())
+            .collect();
+        for ticket in expired {
+            self.remove(&ticket);
+        }
+    }
+
+    /// Creates the game if the group has enough players.
+    fn try_match(&mut self, rules: &Rules, games: &Games, auth: &Auth) -> Result<(), ApiError> {
+        let players = rules.players as usize;
+        let Some(group) = self
+            .groups
+            .get(rules)
+            .filter(|group| group.len() >= players)
+        else {
+            return Ok(());
+        };
+        let tickets = group[..players].to_vec();
+        let names: Vec<_> = tickets
+            .iter()
+            .map(|ticket| self.entries[ticket].name.clone())
+            .collect();
+
+        let credentials = create_game(names, rules.clone(), games, auth)?;
+        let group = self.groups.get_mut(rules).unwrap();
+        group.drain(..players);
+        if group.is_empty() {
+            self.groups.remove(rules);
+        }
+        for (ticket, credentials) in tickets.iter().zip(credentials) {
+            let entry = self.entries.get_mut(ticket).unwrap();
+            entry.matched = Some(credentials);
+            // Matched players get some time to fetch their credentials.
+            entry.last_seen = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+/// Creates started game with provided players, the first one being the host.
+fn create_game(
+    names: Vec<String>,
+    rules: Rules,
+    games: &Games,
+    auth: &Auth,
+) -> Result<Vec<JoinGameResponse>, ApiError> {
+    let mut names = names.into_iter();
+    let host = names.next().expect("group shouldn't be empty");
+    // Password is not stored, so nobody else can join the game.
+    let game_id = games.create(&CreateGameData {
+        name: host,
+        password: String::new(),
+        rules,
+        public: false,
+    })?;
+    let (nonce, players) = games.with_game(game_id, |game| {
+        let nonce = game.nonce();
+        let lobby = game.lobby_state()?;
+        let mut players = lobby
+            .players()
+            .iter()
+            .map(|player| player.id)
+            .collect::<Vec<_>>();
+        for name in names {
+            players.push(lobby.add_player(name).ok_or(ApiError::TooManyPlayers)?);
+        }
+        // Entering the queue means the players are ready.
+        game.force_start(&mut games.deck_rng())?;
+        Ok::<_, ApiError>((nonce, players))
+    })??;
+    info!("matched {} players into game `{game_id}`", players.len());
+
+    Ok(players
+        .into_iter()
+        .map(|player_id| JoinGameResponse::Ok {
+            game_id,
+            player_id,
+            token: auth.generate_token(game_id, nonce, player_id),
+        })
+        .collect())
+}
+
+impl FromRef<AppState> for Matchmaking {
+    fn from_ref(input: &AppState) -> Self {
+        input.matchmaking.clone()
+    }
+}
+
+/// Enters the matchmaking queue.
+///
+/// Returned ticket should be used to poll the status until the game is created.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/queue",
+    request_body = QueueData,
+    responses(
+        (status = 200, description = "Entered the queue", body = QueueStatus),
+        (status = 400, description = "Malformed request or unsupported rules", body = ErrorResponse),
+        (status = 429, description = "Too many requests", body = ErrorResponse),
+        (status = 503, description = "Server hosts too many games", body = ErrorResponse),
+    ),
+))]
+pub async fn enter_queue(
+    State(matchmaking): State<Matchmaking>,
+    State(games): State<Games>,
+    State(auth): State<Auth>,
+    State(metrics): State<Metrics>,
+    Json(data): Json<QueueData>,
+) -> Result<QueueStatus, ApiError> {
+    let status = matchmaking.enter(data, &games, &auth)?;
+    if matches!(status, QueueStatus::Matched(_)) {
+        metrics.games_created.inc();
+    }
+    Ok(status)
+}
+
+/// Returns status of the queued player.
+///
+/// Must be polled regularly, otherwise player leaves the queue.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/queue/status",
+    params(TicketQuery),
+    responses(
+        (status = 200, description = "Status of the player", body = QueueStatus),
+        (status = 404, description = "Player is not in the queue", body = ErrorResponse),
+    ),
+))]
+pub async fn queue_status(
+    Query(query): Query<TicketQuery>,
+    State(matchmaking): State<Matchmaking>,
+) -> Result<QueueStatus, ApiError> {
+    matchmaking.status(&query.ticket)
+}
+
+/// Leaves the matchmaking queue.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    post,
+    path = "/queue/leave",
+    params(TicketQuery),
+    responses(
+        (status = 200, description = "Left the queue", body = ()),
+        (status = 404, description = "Player is not in the queue", body = ErrorResponse),
+    ),
+))]
+pub async fn leave_queue(
+    Query(query): Query<TicketQuery>,
+    State(matchmaking): State<Matchmaking>,
+) -> Result<Json<()>, ApiError> {
+    matchmaking.leave(&query.ticket)?;
+    Ok(Json(()))
+}
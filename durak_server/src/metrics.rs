@@ -0,0 +1,200 @@
+//! Prometheus metrics of the server.
+
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{FromRef, MatchedPath, State},
+    http::{header::CONTENT_TYPE, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use durak_lib::errors::ApiError;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+
+use crate::state::{AppState, Games};
+
+/// Collection of all metrics exported by the server.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    /// Number of games waiting in lobby.
+    pub lobbies: IntGauge,
+    /// Number of games with ongoing round.
+    pub rounds: IntGauge,
+    /// Number of created games.
+    pub games_created: IntCounter,
+    /// Number of players that joined games.
+    pub games_joined: IntCounter,
+    /// Number of games that were played to the end.
+    pub games_finished: IntCounter,
+    /// Number of handled requests by route, method and status code.
+    pub requests: IntCounterVec,
+    /// Latency of handled requests by route and method.
+    pub request_duration: HistogramVec,
+    /// Number of failed authorizations by reason.
+    pub auth_failures: IntCounterVec,
+    /// Time spent waiting for the lock on a game in [Games].
+    pub games_lock_wait: Histogram,
+}
+
+impl Metrics {
+    /// Creates new set of metrics registered in its own registry.
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("durak".to_owned()), None).unwrap();
+        let metrics = Self {
+            lobbies: IntGauge::new("lobbies", "Number of games waiting in lobby").unwrap(),
+            rounds: IntGauge::new("rounds", "Number of games with ongoing round").unwrap(),
+            games_created: IntCounter::new("games_created_total", "Number of created games")
+                .unwrap(),
+            games_joined: IntCounter::new(
+                "games_joined_total",
+                "Number of players that joined games",
+            )
+            .unwrap(),
+            games_finished: IntCounter::new(
+                "games_finished_total",
+                "Number of games that were played to the end",
+            )
+            .unwrap(),
+            requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "Number of handled requests"),
+                &["route", "method", "status"],
+            )
+            .unwrap(),
+            request_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "http_request_duration_seconds",
+                    "Latency of handled requests",
+                ),
+                &["route", "method"],
+            )
+            .unwrap(),
+            auth_failures: IntCounterVec::new(
+                Opts::new("auth_failures_total", "Number of failed authorizations"),
+                &["reason"],
+            )
+            .unwrap(),
+            games_lock_wait: Histogram::with_opts(
+                HistogramOpts::new(
+                    "games_lock_wait_seconds",
+                    "Time spent waiting for the lock on a game",
+                )
+                .buckets(prometheus::exponential_buckets(1e-6, 4.0, 10).unwrap()),
+            )
+            .unwrap(),
+            registry,
+        };
+        metrics.register();
+        metrics
+    }
+
+    fn register(&self) {
+        let collectors: [Box<dyn prometheus::core::Collector>; 9] = [
+            Box::new(self.lobbies.clone()),
+            Box::new(self.rounds.clone()),
+            Box::new(self.games_created.clone()),
+            Box::new(self.games_joined.clone()),
+            Box::new(self.games_finished.clone()),
+            Box::new(self.requests.clone()),
+            Box::new(self.request_duration.clone()),
+            Box::new(self.auth_failures.clone()),
+            Box::new(self.games_lock_wait.clone()),
+        ];
+        for collector in collectors {
+            self.registry.register(collector).unwrap();
+        }
+    }
+
+    /// Records failed authorization.
+    pub fn auth_failed(&self, error: &ApiError) {
+        let reason = match error {
+            ApiError::AuthFailed(reason) => format!("{reason:?}"),
+            other => format!("{other:?}"),
+        };
+        self.auth_failures.with_label_values(&[&reason]).inc();
+    }
+
+    /// Encodes all metrics in Prometheus text format.
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromRef<AppState> for Metrics {
+    fn from_ref(input: &AppState) -> Self {
+        input.metrics.clone()
+    }
+}
+
+/// Middleware that counts requests and measures their latency.
+///
+/// Should be added with `route_layer`, so matched route is known.
+pub async fn track_requests(
+    State(metrics): State<Metrics>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| request.uri().path().to_owned());
+    let method = request.method().to_string();
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    metrics
+        .request_duration
+        .with_label_values(&[&route, &method])
+        .observe(started.elapsed().as_secs_f64());
+    metrics
+        .requests
+        .with_label_values(&[&route, &method, response.status().as_str()])
+        .inc();
+    response
+}
+
+/// Exports metrics in Prometheus text format.
+#[cfg_attr(feature = "openapi", utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Metrics in Prometheus text format", body = String, content_type = "text/plain")),
+))]
+pub async fn metrics(State(metrics): State<Metrics>, State(games): State<Games>) -> Response {
+    let (lobbies, rounds) = games.count_active();
+    metrics.lobbies.set(lobbies as i64);
+    metrics.rounds.set(rounds as i64);
+    ([(CONTENT_TYPE, prometheus::TEXT_FORMAT)], metrics.encode()).into_response()
+}
+
+#[cfg(test)]
+mod test {
+    use durak_lib::errors::{ApiError, AuthFailed};
+
+    use super::Metrics;
+
+    #[test]
+    fn test_encoded_metrics() {
+        let metrics = Metrics::new();
+        metrics.games_created.inc();
+        metrics.auth_failed(&ApiError::AuthFailed(AuthFailed::TokenExpired));
+
+        let encoded = metrics.encode();
+        assert!(encoded.contains("durak_games_created_total 1"));
+        assert!(encoded.contains(r#"durak_auth_failures_total{reason="TokenExpired"} 1"#));
+    }
+}
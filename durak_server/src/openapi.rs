@@ -0,0 +1,156 @@
+//! OpenAPI description of the server.
+//!
+//! Document is generated from [durak_lib] types and handler annotations,
+//! and is served at `/openapi.json` when the `openapi` feature is enabled.
+//! A copy is kept in `durak_server/openapi.json`.
+
+use axum::Json;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Durak",
+        description = "HTTP API of the Durak game server.",
+        license(name = "MIT"),
+    ),
+    paths(
+        crate::healthz,
+        crate::readyz,
+        crate::metrics::metrics,
+        crate::version,
+        crate::lobbies,
+        crate::create_game,
+        crate::join_game,
+        crate::spectate,
+        crate::spectator_status,
+        crate::matchmaking::enter_queue,
+        crate::matchmaking::queue_status,
+        crate::matchmaking::leave_queue,
+        crate::toggle_ready,
+        crate::start,
+        crate::status,
+        crate::play_card,
+        crate::take,
+        crate::retreat,
+        crate::leave,
+        crate::kick,
+        crate::swap_seats,
+        crate::post_message,
+        crate::admin::list_games,
+        crate::admin::inspect_game,
+        crate::admin::finish_game,
+        crate::admin::kick_player,
+        crate::admin::delete_game,
+        openapi,
+    ),
+    modifiers(&BearerToken),
+)]
+pub struct ApiDoc;
+
+/// Adds bearer token scheme that is used by all `/game` endpoints
+/// and admin key scheme that is used by `/admin` endpoints.
+struct BearerToken;
+
+impl Modify for BearerToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for name in ["token", "admin_key"] {
+            components.add_security_scheme(
+                name,
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+/// Returns OpenAPI description of the server.
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    responses((status = 200, description = "This document", content_type = "application/json")),
+)]
+pub async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{Method, Request, StatusCode},
+    };
+    use durak_lib::errors::ApiError;
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use crate::config::Config;
+
+    use super::ApiDoc;
+
+    /// Committed copy of the document.
+    const SPEC_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+    /// Documented operations as `(method, path)` pairs.
+    fn documented_routes() -> Vec<(String, String)> {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut routes = Vec::new();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                routes.push((method.to_uppercase(), path.clone()));
+            }
+        }
+        routes.sort();
+        routes
+    }
+
+    #[test]
+    fn test_spec_is_up_to_date() {
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(SPEC_PATH, &generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(SPEC_PATH).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "`openapi.json` is outdated, rerun tests with `UPDATE_OPENAPI=1` to regenerate it"
+        );
+    }
+
+    /// Every documented operation is routed, and documented paths accept no other methods.
+    #[tokio::test]
+    async fn test_routes_match_documentation() {
+        let router = crate::router(&Config {
+            admin_key: Some(String::from("admin key for tests")),
+            ..Config::default()
+        });
+        let documented = documented_routes();
+
+        for (_, path) in &documented {
+            for method in [Method::GET, Method::POST, Method::PUT, Method::DELETE] {
+                let is_documented = documented.contains(&(method.to_string(), path.clone()));
+                let mut request = Request::builder()
+                    .method(method.clone())
+                    .uri(path)
+                    .body(Body::empty())
+                    .unwrap();
+                request
+                    .extensions_mut()
+                    .insert(ConnectInfo(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))));
+                let response = router.clone().oneshot(request).await.unwrap();
+                let is_routed = response.status() != StatusCode::METHOD_NOT_ALLOWED
+                    && !matches!(response.extensions().get(), Some(ApiError::NotFound));
+                assert_eq!(
+                    is_routed, is_documented,
+                    "`{method} {path}` is routed: {is_routed}, documented: {is_documented}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! Rate limiting of unauthenticated endpoints and the chat.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{ConnectInfo, FromRef, State},
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use durak_lib::{
+    errors::{ApiError, RateLimited},
+    identifiers::{GameId, PlayerId},
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{config::RateLimits, state::AppState};
+
+/// State of all rate limits.
+#[derive(Clone)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    by_ip: Entries<IpAddr, Window>,
+    by_game: Entries<(IpAddr, GameId), Window>,
+    by_player: Entries<(GameId, PlayerId), Window>,
+    failures: Entries<(IpAddr, GameId), Failures>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            state: Arc::new(Mutex::new(LimiterState::default())),
+        }
+    }
+
+    /// Registers request from provided address.
+    pub fn hit_ip(&self, ip: IpAddr) -> Result<(), RateLimited> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let (limit, period) = (self.limits.per_ip, self.limits.period);
+        state.by_ip.prune(|window| window.is_expired(now, period));
+        state
+            .by_ip
+            .entries
+            .entry(ip)
+            .or_insert_with(|| Window::new(now))
+            .hit(now, limit, period)
+    }
+
+    /// Registers attempt to join the game from provided address.
+    ///
+    /// Fails if address makes too many attempts to join the game or is locked out of it.
+    pub fn hit_game(&self, ip: IpAddr, game_id: GameId) -> Result<(), RateLimited> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if let Some(locked_until) = state
+            .failures
+            .entries
+            .get(&(ip, game_id))
+            .and_then(|failures| failures.locked_until)
+            .filter(|locked_until| *locked_until > now)
+        {
+            return Err(rate_limited(locked_until - now));
+        }
+        let (limit, period) = (self.limits.per_game, self.limits.period);
+        state.by_game.prune(|window| window.is_expired(now, period));
+        state
+            .by_game
+            .entries
+            .entry((ip, game_id))
+            .or_insert_with(|| Window::new(now))
+            .hit(now, limit, period)
+    }
+
+    /// Registers chat message posted by the player.
+    pub fn hit_chat(&self, game_id: GameId, player_id: PlayerId) -> Result<(), RateLimited> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let (limit, period) = (self.limits.chat, self.limits.period);
+        state
+            .by_player
+            .prune(|window| window.is_expired(now, period));
+        state
+            .by_player
+            .entries
+            .entry((game_id, player_id))
+            .or_insert_with(|| Window::new(now))
+            .hit(now, limit, period)
+    }
+
+    /// Records failed password attempt.
+    ///
+    /// Address is locked out of the game after too many failed attempts in a row.
+    pub fn record_failure(&self, ip: IpAddr, game_id: GameId) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.failures.prune(|failures| failures.is_expired(now));
+        let failures = state.failures.entries.entry((ip, game_id)).or_default();
+        failures.count += 1;
+        failures.last = Some(now);
+        if failures.count >= self.limits.lockout_threshold {
+            info!("locked out `{ip}` from game `{game_id}` after repeated wrong passwords");
+            failures.count = 0;
+            failures.locked_until = Some(now + self.limits.lockout_duration);
+        }
+    }
+
+    /// Forgets failed password attempts after successful join.
+    pub fn forget_failures(&self, ip: IpAddr, game_id: GameId) {
+        let mut state = self.state.lock().unwrap();
+        state.failures.entries.remove(&(ip, game_id));
+    }
+}
+
+impl FromRef<AppState> for RateLimiter {
+    fn from_ref(input: &AppState) -> Self {
+        input.rate_limiter.clone()
+    }
+}
+
+/// Fixed window request counter.
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+impl Window {
+    fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            count: 0,
+        }
+    }
+
+    fn is_expired(&self, now: Instant, period: Duration) -> bool {
+        now.duration_since(self.started) >= period
+    }
+
+    fn hit(&mut self, now: Instant, limit: u32, period: Duration) -> Result<(), RateLimited> {
+        if self.is_expired(now, period) {
+            *self = Window::new(now);
+        }
+        if self.count >= limit {
+            return Err(rate_limited(period - now.duration_since(self.started)));
+        }
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Failed password attempts.
+#[derive(Default)]
+struct Failures {
+    count: u32,
+    last: Option<Instant>,
+    locked_until: Option<Instant>,
+}
+
+impl Failures {
+    /// Failures are forgotten one hour after the last one, unless lockout is still active.
+    const MEMORY: Duration = Duration::from_secs(60 * 60);
+
+    fn is_expired(&self, now: Instant) -> bool {
+        let locked = self.locked_until.is_some_and(|until| until > now);
+        let recent = self
+            .last
+            .is_some_and(|last| now.duration_since(last) < Self::MEMORY);
+        !locked && !recent
+    }
+}
+
+/// Number of tracked entries after which expired ones are removed.
+const PRUNE_THRESHOLD: usize = 4096;
+
+/// Tracked entries that are pruned once their number doubles since the last pruning.
+struct Entries<K, V> {
+    entries: HashMap<K, V>,
+    prune_at: usize,
+}
+
+impl<K, V> Default for Entries<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            prune_at: PRUNE_THRESHOLD,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> Entries<K, V> {
+    /// Removes expired entries if map grew too large.
+    fn prune(&mut self, is_expired: impl Fn(&V) -> bool) {
+        if self.entries.len() >= self.prune_at {
+            self.entries.retain(|_, value| !is_expired(value));
+            self.prune_at = PRUNE_THRESHOLD.max(self.entries.len() * 2);
+        }
+    }
+}
+
+fn rate_limited(retry_after: Duration) -> RateLimited {
+    RateLimited {
+        retry_after: retry_after.as_secs().max(1),
+    }
+}
+
+/// Middleware that limits number of requests from single IP address.
+pub async fn limit_by_ip<B>(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if let Err(rejection) = limiter.hit_ip(addr.ip()) {
+        info!("rate limited `{}` on `{}`", addr.ip(), request.uri().path());
+        return ApiError::from(rejection).into_response();
+    }
+    next.run(request).await
+}
+
+/// Middleware that limits join attempts to each game from single address.
+///
+/// Addresses that repeatedly fail to provide correct password are temporarily locked out.
+pub async fn limit_join_attempts(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    #[derive(Deserialize)]
+    struct JoinTarget {
+        id: GameId,
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match read_limited(body, MAX_JOIN_BODY).await {
+        Ok(bytes) => bytes,
+        Err(err) => return err.into_response(),
+    };
+    let target = serde_json::from_slice::<JoinTarget>(&bytes).ok();
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    // Malformed requests are rejected by the handler itself.
+    let Some(JoinTarget { id: game_id }) = target else {
+        return next.run(request).await;
+    };
+
+    if let Err(rejection) = limiter.hit_game(addr.ip(), game_id) {
+        info!(
+            "rate limited join attempt from `{}` to `{game_id}`",
+            addr.ip()
+        );
+        return ApiError::from(rejection).into_response();
+    }
+    let response = next.run(request).await;
+    if let Some(ApiError::InvalidPassword) = response.extensions().get::<ApiError>() {
+        limiter.record_failure(addr.ip(), game_id);
+    } else if response.status().is_success() {
+        limiter.forget_failures(addr.ip(), game_id);
+    }
+    response
+}
+
+/// Maximum size of join request body buffered by [`limit_join_attempts`].
+const MAX_JOIN_BODY: usize = 4 * 1024;
+
+/// Buffers request body, failing if it exceeds `limit` bytes.
+async fn read_limited(mut body: Body, limit: usize) -> Result<Bytes, ApiError> {
+    if body.size_hint().lower() > limit as u64 {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| ApiError::BadRequest(err.to_string()))?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(ApiError::PayloadTooLarge);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes.into())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        time::Duration,
+    };
+
+    use durak_lib::identifiers::GameId;
+
+    use crate::config::RateLimits;
+
+    use super::RateLimiter;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn test_ip_limit() {
+        let limiter = RateLimiter::new(RateLimits {
+            per_ip: 2,
+            period: Duration::from_secs(60),
+            ..Default::default()
+        });
+        assert!(limiter.hit_ip(IP).is_ok());
+        assert!(limiter.hit_ip(IP).is_ok());
+        assert!(
+            limiter.hit_ip(IP).is_err(),
+            "third request should be limited"
+        );
+    }
+
+    #[test]
+    fn test_lockout_after_failures() {
+        let limiter = RateLimiter::new(RateLimits {
+            lockout_threshold: 2,
+            ..Default::default()
+        });
+        let game_id = GameId::new(1);
+        limiter.record_failure(IP, game_id);
+        assert!(limiter.hit_game(IP, game_id).is_ok());
+        limiter.record_failure(IP, game_id);
+        assert!(
+            limiter.hit_game(IP, game_id).is_err(),
+            "address should be locked out"
+        );
+        assert!(
+            limiter.hit_game(IP, GameId::new(2)).is_ok(),
+            "lockout should only affect single game"
+        );
+    }
+
+    #[test]
+    fn test_game_limit_is_per_address() {
+        let limiter = RateLimiter::new(RateLimits {
+            per_game: 1,
+            ..Default::default()
+        });
+        let game_id = GameId::new(1);
+        assert!(limiter.hit_game(IP, game_id).is_ok());
+        assert!(limiter.hit_game(IP, game_id).is_err());
+        assert!(
+            limiter
+                .hit_game(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), game_id)
+                .is_ok(),
+            "other addresses should still be able to join"
+        );
+    }
+}
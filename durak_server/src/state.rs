@@ -2,53 +2,345 @@
 
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard, RwLock},
+    time::{Duration, Instant},
 };
 
+use argon2::{
+    password_hash::{rand_core::OsRng, SaltString},
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+};
 use axum::extract::FromRef;
 use durak_lib::{
-    errors::{AccessError, GameNotFound},
+    admin::{GameSummary, PhaseKind},
+    errors::{AccessError, ApiError, AuthFailed, GameNotFound},
+    game::rules::Rules,
     identifiers::{GameId, PlayerId},
-    network::Token,
+    network::{CreateGameData, LobbySummary, Token},
+};
+use hmac::{Hmac, Mac};
+use prometheus::Histogram;
+use rand::{rngs::StdRng, SeedableRng};
+use sha2::Sha256;
+use tokio::task::{self, JoinHandle};
+use tracing::info;
+
+use crate::{
+    auth::token::{unix_now, Claims, TokenSigner},
+    config::{Config, Expiry, GameLimits, PasswordHashing},
+    game::{lobby::LobbyState, round::RoundState, Game, GamePhase},
+    matchmaking::Matchmaking,
+    metrics::Metrics,
+    rate_limit::RateLimiter,
 };
-use rand::{thread_rng, Rng};
 
-use crate::game::{lobby::LobbyState, round::RoundState, Game};
+/// How often expired games are looked for.
+const EXPIRY_CHECK_PERIOD: Duration = Duration::from_secs(60);
+/// How often timed out turns are looked for.
+const TURN_TIMEOUT_CHECK_PERIOD: Duration = Duration::from_secs(1);
 
 #[derive(Clone)]
 pub struct AppState {
     pub(crate) auth: Auth,
     pub(crate) games: Games,
+    pub(crate) matchmaking: Matchmaking,
+    pub(crate) rate_limiter: RateLimiter,
+    pub(crate) metrics: Metrics,
 }
 
 impl AppState {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
+        let metrics = Metrics::new();
         Self {
-            auth: Auth::new(),
-            games: Games::new(),
+            auth: Auth::new(config),
+            games: Games::new(
+                config.limits,
+                config.deck_seed,
+                metrics.games_lock_wait.clone(),
+            ),
+            matchmaking: Matchmaking::default(),
+            rate_limiter: RateLimiter::new(config.rate_limits),
+            metrics,
+        }
+    }
+
+    /// Spawns task that periodically removes expired games and times out turns.
+    pub fn spawn_expiry_task(&self, expiry: Expiry) -> JoinHandle<()> {
+        let state = self.clone();
+        tokio::spawn(async move {
+            let mut expiry_interval = tokio::time::interval(EXPIRY_CHECK_PERIOD);
+            let mut timeout_interval = tokio::time::interval(TURN_TIMEOUT_CHECK_PERIOD);
+            loop {
+                tokio::select! {
+                    _ = expiry_interval.tick() => {
+                        state.remove_expired_games(&expiry);
+                        state.matchmaking.remove_expired();
+                    }
+                    _ = timeout_interval.tick() => state.games.apply_turn_timeouts(),
+                }
+            }
+        })
+    }
+
+    /// Verifies the token and checks that it was issued for the live game with its id.
+    ///
+    /// Returns data carried by the token if it is valid.
+    pub fn validate_token(&self, token: &Token) -> Result<Claims, ApiError> {
+        let claims = self.auth.validate_token(token)?;
+        // Token was issued for an earlier game that had the same id.
+        if self.games.nonce(claims.game_id)? != claims.game_nonce {
+            return Err(GameNotFound.into());
+        }
+        Ok(claims)
+    }
+
+    /// Removes games that weren't active for too long.
+    pub fn remove_expired_games(&self, expiry: &Expiry) {
+        for game_id in self.games.remove_expired(expiry) {
+            self.auth.forget_game(game_id);
+            info!("removed expired game `{game_id}`");
         }
     }
 }
 
 /// Collection of all ongoing games.
-#[derive(Debug, Clone)]
-pub struct Games(Arc<Mutex<HashMap<GameId, Game>>>);
+///
+/// Every game has its own lock, so requests to different games don't block each other.
+/// The map itself is locked for writing only to create and remove games.
+#[derive(Clone)]
+pub struct Games {
+    games: Arc<RwLock<HashMap<GameId, SharedGame>>>,
+    limits: GameLimits,
+    deck_seed: Option<u64>,
+    /// Time spent waiting for the lock on a game.
+    lock_wait: Histogram,
+}
+
+type SharedGame = Arc<Mutex<Game>>;
 
 impl Games {
-    fn new() -> Self {
-        Self(Arc::new(Mutex::new(HashMap::with_capacity(128))))
+    fn new(limits: GameLimits, deck_seed: Option<u64>, lock_wait: Histogram) -> Self {
+        Self {
+            games: Arc::new(RwLock::new(HashMap::with_capacity(128))),
+            limits,
+            deck_seed,
+            lock_wait,
+        }
+    }
+
+    /// Looks up the game.
+    fn get(&self, id: GameId) -> Result<SharedGame, GameNotFound> {
+        let games = self.games.read().unwrap();
+        games.get(&id).cloned().ok_or(GameNotFound)
+    }
+
+    /// Locks the game, recording time spent waiting.
+    fn lock<'a>(&self, game: &'a SharedGame) -> MutexGuard<'a, Game> {
+        let timer = self.lock_wait.start_timer();
+        let game = game.lock().unwrap();
+        timer.observe_duration();
+        game
+    }
+
+    /// Returns snapshot of all games.
+    fn all(&self) -> Vec<(GameId, SharedGame)> {
+        let games = self.games.read().unwrap();
+        games.iter().map(|(id, game)| (*id, game.clone())).collect()
+    }
+
+    /// Returns random number generator used to shuffle the deck.
+    ///
+    /// It is seeded with configured seed, if there is one.
+    pub fn deck_rng(&self) -> StdRng {
+        match self.deck_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+
+    /// Returns `true` if no more games may be created.
+    pub fn is_full(&self) -> bool {
+        self.games.read().unwrap().len() >= self.limits.max_games
+    }
+
+    /// Counts games in lobby and games with ongoing round.
+    pub fn count_active(&self) -> (usize, usize) {
+        let (mut lobbies, mut rounds) = (0, 0);
+        for (_, game) in self.all() {
+            match self.lock(&game).phase {
+                GamePhase::Lobby(_) => lobbies += 1,
+                GamePhase::Round(_) => rounds += 1,
+                GamePhase::Finished(_) => {}
+            }
+        }
+        (lobbies, rounds)
+    }
+
+    /// Creates new game hosted by the requesting player, with a join code unused by live games.
+    ///
+    /// Game isn't listed publicly, since its password is stored afterwards.
+    /// Fails if the server already hosts maximum number of games.
+    pub fn create(&self, data: &CreateGameData) -> Result<GameId, ApiError> {
+        self.validate_name(&data.name)?;
+        self.validate_rules(&data.rules)?;
+
+        let mut game = Game::new(data.rules.clone(), self.limits.start_countdown);
+        let lobby = game.lobby_state().unwrap();
+        lobby.add_player(data.name.clone());
+
+        let mut games = self.games.write().unwrap();
+        if games.len() >= self.limits.max_games {
+            return Err(ApiError::TooManyGames);
+        }
+        // Join codes are short, so collisions with live games are possible.
+        let mut rng = rand::thread_rng();
+        let id = loop {
+            let id = GameId::random(&mut rng);
+            if !games.contains_key(&id) {
+                break id;
+            }
+        };
+        games.insert(id, Arc::new(Mutex::new(game)));
+        Ok(id)
+    }
+
+    /// Checks that player name fits the limits.
+    pub fn validate_name(&self, name: &str) -> Result<(), ApiError> {
+        if name.chars().count() > self.limits.max_name_length {
+            return Err(ApiError::BadRequest(format!(
+                "name is longer than {} characters",
+                self.limits.max_name_length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that chat message is not empty and fits the limits.
+    pub fn validate_message(&self, text: &str) -> Result<(), ApiError> {
+        if text.is_empty() {
+            return Err(ApiError::BadRequest(String::from("message is empty")));
+        }
+        if text.chars().count() > self.limits.max_message_length {
+            return Err(ApiError::BadRequest(format!(
+                "message is longer than {} characters",
+                self.limits.max_message_length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that the server can host games with provided rules.
+    pub fn validate_rules(&self, rules: &Rules) -> Result<(), ApiError> {
+        if !(2..=Rules::MAX_PLAYERS).contains(&rules.players) {
+            return Err(ApiError::BadRequest(format!(
+                "games for {} players are not supported",
+                rules.players
+            )));
+        }
+        if rules.turn_time == Some(0) {
+            return Err(ApiError::BadRequest(String::from(
+                "turn time must be positive",
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns public lobbies that may be joined.
+    pub fn lobbies(&self) -> Vec<LobbySummary> {
+        let mut lobbies: Vec<_> = self
+            .all()
+            .into_iter()
+            .filter_map(|(id, game)| match &self.lock(&game).phase {
+                GamePhase::Lobby(lobby) => lobby.summary(id),
+                _ => None,
+            })
+            .collect();
+        // Lobbies that are about to start go first.
+        lobbies.sort_by(|a, b| b.players.cmp(&a.players).then(a.host.cmp(&b.host)));
+        lobbies
     }
 
-    /// Creates new game with provided password.
-    pub fn create(&self, name: String) -> GameId {
-        let id = GameId::new(rand::thread_rng().gen());
-        let mut games = self.0.lock().unwrap();
+    /// Removes games that weren't active for too long.
+    ///
+    /// Returns ids of removed games.
+    fn remove_expired(&self, expiry: &Expiry) -> Vec<GameId> {
+        let is_expired = |game: &SharedGame| self.lock(game).is_expired(Instant::now(), expiry);
+        let expired: Vec<_> = self
+            .all()
+            .into_iter()
+            .filter(|(_, game)| is_expired(game))
+            .map(|(id, _)| id)
+            .collect();
+        if expired.is_empty() {
+            return expired;
+        }
+
+        let mut games = self.games.write().unwrap();
+        expired
+            .into_iter()
+            // Game might have been used since the first check.
+            .filter(|id| match games.get(id) {
+                Some(game) if is_expired(game) => games.remove(id).is_some(),
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// Acts on behalf of players whose turn time is over, in every game.
+    ///
+    /// Unlike other accessors, it doesn't mark games as active.
+    pub fn apply_turn_timeouts(&self) {
+        for (id, game) in self.all() {
+            if self.lock(&game).apply_turn_timeout() {
+                info!("turn timed out in game `{id}`");
+            }
+        }
+    }
+
+    /// Returns short description of every game.
+    pub fn summaries(&self) -> Vec<GameSummary> {
+        let now = Instant::now();
+        let mut summaries: Vec<_> = self
+            .all()
+            .into_iter()
+            .map(|(id, game)| {
+                let game = self.lock(&game);
+                GameSummary {
+                    id,
+                    phase: match game.phase {
+                        GamePhase::Lobby(_) => PhaseKind::Lobby,
+                        GamePhase::Round(_) => PhaseKind::Round,
+                        GamePhase::Finished(_) => PhaseKind::Finished,
+                    },
+                    players: game.players().to_vec(),
+                    idle_secs: game.idle_time(now).as_secs(),
+                }
+            })
+            .collect();
+        summaries.sort_by_key(|summary| summary.idle_secs);
+        summaries
+    }
+
+    /// Returns full state of the game serialized as JSON.
+    ///
+    /// Unlike other accessors, it doesn't mark game as active.
+    pub fn inspect(&self, id: GameId) -> Result<serde_json::Value, GameNotFound> {
+        let game = self.get(id)?;
+        let game = self.lock(&game);
+        Ok(serde_json::to_value(&game.phase).expect("game state should be serializable"))
+    }
 
-        let mut game = Game::new();
-        game.lobby_state().unwrap().add_player(name);
+    /// Returns nonce of the game, without marking it as active.
+    pub fn nonce(&self, id: GameId) -> Result<u64, GameNotFound> {
+        let game = self.get(id)?;
+        let game = self.lock(&game);
+        Ok(game.nonce())
+    }
 
-        games.insert(id, game);
-        id
+    /// Removes the game.
+    pub fn remove(&self, id: GameId) -> Result<(), GameNotFound> {
+        let mut games = self.games.write().unwrap();
+        games.remove(&id).map(|_| ()).ok_or(GameNotFound)
     }
 
     /// Evaluates provided function with mutable reference to game.
@@ -57,9 +349,14 @@ impl Games {
         id: GameId,
         func: impl FnOnce(&mut Game) -> T,
     ) -> Result<T, GameNotFound> {
-        let mut games = self.0.lock().unwrap();
-        let game = games.get_mut(&id).ok_or(GameNotFound)?;
-        Ok(func(game))
+        let game = self.get(id)?;
+        let mut game = self.lock(&game);
+        game.touch();
+        // Timeouts are also applied periodically, this keeps the response up to date.
+        if game.apply_turn_timeout() {
+            info!("turn timed out in game `{id}`");
+        }
+        Ok(func(&mut game))
     }
 
     /// Evaluates provided function with mutable reference to [LobbyState].
@@ -68,10 +365,7 @@ impl Games {
         id: GameId,
         func: impl FnOnce(&mut LobbyState) -> T,
     ) -> Result<T, AccessError> {
-        let mut games = self.0.lock().unwrap();
-        let game = games.get_mut(&id).ok_or(GameNotFound)?;
-        let round = game.lobby_state()?;
-        Ok(func(round))
+        self.with_game(id, |game| Ok(func(game.lobby_state()?)))?
     }
 
     /// Evaluates provided function with mutable reference to [RoundState].
@@ -80,10 +374,7 @@ impl Games {
         id: GameId,
         func: impl FnOnce(&mut RoundState) -> T,
     ) -> Result<T, AccessError> {
-        let mut games = self.0.lock().unwrap();
-        let game = games.get_mut(&id).ok_or(GameNotFound)?;
-        let round = game.round_state()?;
-        Ok(func(round))
+        self.with_game(id, |game| Ok(func(game.round_state()?)))?
     }
 }
 
@@ -93,62 +384,157 @@ impl FromRef<AppState> for Games {
     }
 }
 
-/// State that stores all authentication tokens and associated data.
+/// State that stores lobby passwords and issues authentication tokens.
 #[derive(Clone)]
 pub struct Auth {
+    hasher: Argon2<'static>,
+    /// Password hashes in PHC string format.
     passwords: Arc<Mutex<HashMap<GameId, String>>>,
-    sessions: Arc<Mutex<HashMap<Token, (GameId, PlayerId)>>>,
+    tokens: TokenSigner,
+    /// Time of removal of kicked players, tokens issued before it are rejected.
+    kicked: Arc<Mutex<HashMap<(GameId, PlayerId), u64>>>,
+    /// MAC of the admin key, if admin endpoints are enabled.
+    admin_key: Option<Vec<u8>>,
 }
 
 impl Auth {
     /// Creates new auth state manager.
-    fn new() -> Self {
+    ///
+    /// # Panics
+    ///
+    /// Panics if password hashing parameters are invalid.
+    fn new(config: &Config) -> Self {
+        let PasswordHashing {
+            memory_cost,
+            time_cost,
+            parallelism,
+        } = config.password_hashing;
+        let params = Params::new(memory_cost, time_cost, parallelism, None)
+            .expect("password hashing parameters should be valid");
         let passwords = HashMap::with_capacity(64);
-        let sessions = HashMap::with_capacity(128);
         Self {
+            hasher: Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
             passwords: Arc::new(Mutex::new(passwords)),
-            sessions: Arc::new(Mutex::new(sessions)),
+            tokens: TokenSigner::new(&config.token_key, config.expiry.token),
+            kicked: Arc::new(Mutex::new(HashMap::new())),
+            admin_key: config.admin_key.as_ref().map(|key| {
+                let mut mac = admin_key_mac();
+                mac.update(key.as_bytes());
+                mac.finalize().into_bytes().to_vec()
+            }),
         }
     }
 
-    /// Creates new password entry.
+    /// Hashes password with a random salt and creates new password entry.
     ///
     /// # Panics
     ///
     /// Panics if called more than once for provided [GameId].
-    pub fn store_password(&self, game_id: GameId, password: String) {
+    pub async fn store_password(&self, game_id: GameId, password: String) {
+        let hasher = self.hasher.clone();
+        let hash = task::spawn_blocking(move || {
+            let salt = SaltString::generate(&mut OsRng);
+            hasher
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+        })
+        .await
+        .expect("password hashing task shouldn't panic")
+        .expect("password hashing shouldn't fail");
+
         let mut passwords = self.passwords.lock().unwrap();
-        if passwords.insert(game_id, password).is_some() {
+        if passwords.insert(game_id, hash).is_some() {
             panic!("Attempted to create new password entry when it is already present");
         }
     }
 
-    /// Generates new token.
-    pub fn generate_token(&self, game_id: GameId, player_id: PlayerId) -> Token {
-        let mut sessions = self.sessions.lock().unwrap();
-        let token = Token::new(thread_rng().gen());
-        sessions.insert(token, (game_id, player_id));
-        token
+    /// Removes password entry and kicked players of the game.
+    pub fn forget_game(&self, game_id: GameId) {
+        self.passwords.lock().unwrap().remove(&game_id);
+        self.kicked
+            .lock()
+            .unwrap()
+            .retain(|(kicked_from, _), _| *kicked_from != game_id);
     }
 
-    /// Returns `true` if provided password matches stored one.
-    pub fn validate_password(&self, game_id: GameId, password: &str) -> bool {
-        let passwords = self.passwords.lock().unwrap();
-        if let Some(stored_password) = passwords.get(&game_id) {
-            stored_password.as_str() == password
-        } else {
-            false
-        }
+    /// Invalidates all tokens issued to the player so far.
+    pub fn revoke_tokens(&self, game_id: GameId, player_id: PlayerId) {
+        let mut kicked = self.kicked.lock().unwrap();
+        kicked.insert((game_id, player_id), unix_now());
+    }
+
+    /// Generates new signed token.
+    pub fn generate_token(&self, game_id: GameId, game_nonce: u64, player_id: PlayerId) -> Token {
+        self.tokens.issue(game_id, game_nonce, player_id)
     }
 
-    /// Returns `true` if provided token exists and matches provided [GameId] and [PlayerId].
-    pub fn validate_session(&self, token: Token, game_id: GameId, player_id: PlayerId) -> bool {
-        let sessions = self.sessions.lock().unwrap();
-        let Some(data) = sessions.get(&token) else {
-            return false;
+    /// Generates new signed read-only token for the spectator.
+    pub fn generate_spectator_token(&self, game_id: GameId, game_nonce: u64) -> Token {
+        self.tokens.issue_spectator(game_id, game_nonce)
+    }
+
+    /// Returns `true` if provided password matches stored hash.
+    ///
+    /// Hashes are compared in constant time.
+    pub async fn validate_password(
+        &self,
+        game_id: GameId,
+        password: String,
+    ) -> Result<bool, GameNotFound> {
+        let stored_hash = {
+            let passwords = self.passwords.lock().unwrap();
+            passwords.get(&game_id).cloned().ok_or(GameNotFound)?
+        };
+        let hasher = self.hasher.clone();
+        let is_valid = task::spawn_blocking(move || {
+            let Ok(hash) = PasswordHash::new(&stored_hash) else {
+                return false;
+            };
+            hasher.verify_password(password.as_bytes(), &hash).is_ok()
+        })
+        .await
+        .expect("password verification task shouldn't panic");
+        Ok(is_valid)
+    }
+
+    /// Returns `true` if admin key is set.
+    pub fn is_admin_enabled(&self) -> bool {
+        self.admin_key.is_some()
+    }
+
+    /// Checks that provided key matches admin key.
+    ///
+    /// Keys are compared in constant time.
+    pub fn validate_admin_key(&self, key: &str) -> Result<(), AuthFailed> {
+        let Some(expected) = &self.admin_key else {
+            return Err(AuthFailed::TokenDoesntMatch);
         };
-        data.0 == game_id && data.1 == player_id
+        let mut mac = admin_key_mac();
+        mac.update(key.as_bytes());
+        mac.verify_slice(expected)
+            .map_err(|_| AuthFailed::TokenDoesntMatch)
     }
+
+    /// Verifies token signature and expiration time.
+    ///
+    /// Returns data carried by the token if it is valid.
+    pub fn validate_token(&self, token: &Token) -> Result<Claims, AuthFailed> {
+        let claims = self.tokens.verify(token)?;
+        if let Some(player_id) = claims.player_id {
+            let kicked = self.kicked.lock().unwrap();
+            if let Some(kicked_at) = kicked.get(&(claims.game_id, player_id)) {
+                if claims.issued_at <= *kicked_at {
+                    return Err(AuthFailed::Kicked);
+                }
+            }
+        }
+        Ok(claims)
+    }
+}
+
+/// MAC used to compare admin keys in constant time.
+fn admin_key_mac() -> Hmac<Sha256> {
+    Hmac::new_from_slice(b"durak admin key").expect("HMAC should accept key of any size")
 }
 
 impl FromRef<AppState> for Auth {
@@ -156,3 +542,80 @@ impl FromRef<AppState> for Auth {
         input.auth.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use durak_lib::{
+        errors::ApiError, game::rules::Rules, identifiers::PlayerId, network::CreateGameData,
+    };
+
+    use crate::{
+        config::{Config, GameLimits},
+        game::Game,
+        metrics::Metrics,
+    };
+
+    use super::{AppState, Games};
+
+    #[test]
+    fn test_games_are_locked_separately() {
+        let games = Games::new(GameLimits::default(), None, Metrics::new().games_lock_wait);
+        let create = |name: &str| {
+            games
+                .create(&CreateGameData {
+                    name: String::from(name),
+                    password: String::new(),
+                    rules: Rules::default(),
+                    public: false,
+                })
+                .unwrap()
+        };
+        let first = create("first");
+        let second = create("second");
+
+        // Would deadlock if all games shared one lock.
+        let players = games
+            .with_game(first, |_| {
+                games
+                    .with_game(second, |game| game.players().len())
+                    .unwrap()
+            })
+            .unwrap();
+        assert_eq!(players, 1);
+    }
+
+    #[test]
+    fn test_token_of_replaced_game_is_rejected() {
+        let state = AppState::new(&Config::default());
+        let rules = Rules::default();
+        let game_id = state
+            .games
+            .create(&CreateGameData {
+                name: String::from("host"),
+                password: String::new(),
+                rules: rules.clone(),
+                public: false,
+            })
+            .unwrap();
+        let nonce = state.games.nonce(game_id).unwrap();
+        let token = state.auth.generate_token(game_id, nonce, PlayerId::new(0));
+        assert!(state.validate_token(&token).is_ok());
+
+        // New game gets the join code of the removed one.
+        state.games.remove(game_id).unwrap();
+        state.auth.forget_game(game_id);
+        let game = Game::new(rules, Config::default().limits.start_countdown);
+        state
+            .games
+            .games
+            .write()
+            .unwrap()
+            .insert(game_id, Arc::new(Mutex::new(game)));
+        assert!(matches!(
+            state.validate_token(&token),
+            Err(ApiError::GameNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,925 @@
+//! End-to-end tests that drive the router in-process.
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use durak_lib::{
+    chat::{ChatMessage, PostMessageData},
+    errors::{ActionError, ApiError, AuthFailed, ErrorResponse},
+    game::{
+        card::{Card, CardRank, CardSuit},
+        rules::Rules,
+    },
+    identifiers::{GameId, PlayerId},
+    matchmaking::{QueueData, QueueStatus},
+    network::{
+        CreateGameData, CreateGameResponse, JoinGameData, JoinGameResponse, LobbySummary,
+        SpectateData, SpectateResponse, Token,
+    },
+    status::{round::RoundStatus, SpectatorStatus, StatusResponse},
+};
+use durak_server::config::{Config, PasswordHashing};
+use serde::{de::DeserializeOwned, Serialize};
+use tower::ServiceExt;
+
+const SEED: u64 = 42;
+const PASSWORD: &str = "password";
+
+/// Player that joined the game.
+struct Player {
+    game_id: GameId,
+    id: PlayerId,
+    token: Token,
+}
+
+struct TestServer {
+    router: Router,
+}
+
+impl TestServer {
+    fn new() -> Self {
+        let config = Config {
+            deck_seed: Some(SEED),
+            // Password hashing is slow with default parameters.
+            password_hashing: PasswordHashing {
+                memory_cost: 8,
+                time_cost: 1,
+                parallelism: 1,
+            },
+            ..Config::default()
+        };
+        Self {
+            router: durak_server::router(&config),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<impl Serialize>,
+    ) -> (StatusCode, Vec<u8>) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, token);
+        }
+        let body = match body {
+            Some(body) => Body::from(serde_json::to_vec(&body).unwrap()),
+            None => Body::empty(),
+        };
+        let mut request = request.body(body).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))));
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, body.to_vec())
+    }
+
+    /// Sends request that is expected to succeed.
+    async fn ok<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        uri: &str,
+        player: Option<&Player>,
+        body: Option<impl Serialize>,
+    ) -> T {
+        let token = player.map(|player| format!("Bearer {}", player.token.as_str()));
+        let (status, body) = self.send(method, uri, token.as_deref(), body).await;
+        assert!(
+            status.is_success(),
+            "{uri} failed with {status}: {}",
+            String::from_utf8_lossy(&body)
+        );
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    /// Sends request that is expected to fail and returns the error.
+    async fn err(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<impl Serialize>,
+    ) -> (StatusCode, ApiError) {
+        let (status, body) = self.send(method, uri, token, body).await;
+        assert!(!status.is_success(), "{uri} should fail");
+        let response: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        (status, response.error)
+    }
+
+    /// Sends request of the player that is expected to fail and returns the error.
+    async fn player_err(&self, method: Method, uri: &str, player: &Player) -> ApiError {
+        let token = format!("Bearer {}", player.token.as_str());
+        self.err(method, uri, Some(&token), None::<()>).await.1
+    }
+
+    async fn create(&self, name: &str) -> Player {
+        self.create_with(CreateGameData {
+            name: name.to_owned(),
+            password: PASSWORD.to_owned(),
+            rules: Rules::default(),
+            public: false,
+        })
+        .await
+    }
+
+    async fn create_with(&self, data: CreateGameData) -> Player {
+        let CreateGameResponse::Ok {
+            game_id,
+            player_id,
+            token,
+        } = self.ok(Method::POST, "/create", None, Some(data)).await;
+        Player {
+            game_id,
+            id: player_id,
+            token,
+        }
+    }
+
+    async fn join(&self, game_id: GameId, name: &str) -> Player {
+        let JoinGameResponse::Ok {
+            game_id,
+            player_id,
+            token,
+        } = self
+            .ok(
+                Method::POST,
+                "/join",
+                None,
+                Some(join_data(game_id, name, PASSWORD)),
+            )
+            .await;
+        Player {
+            game_id,
+            id: player_id,
+            token,
+        }
+    }
+
+    /// Creates game with two players and starts it.
+    async fn start_game(&self) -> [Player; 2] {
+        let host = self.create("host").await;
+        let guest = self.join(host.game_id, "guest").await;
+        self.start(&host, &guest).await;
+        [host, guest]
+    }
+
+    /// Makes both players ready and starts the game.
+    async fn start(&self, host: &Player, guest: &Player) {
+        for player in [host, guest] {
+            let ready: bool = self
+                .ok(Method::POST, "/game/ready", Some(player), None::<()>)
+                .await;
+            assert!(ready);
+        }
+        self.ok::<()>(Method::POST, "/game/start", Some(host), None::<()>)
+            .await;
+    }
+
+    async fn status(&self, player: &Player) -> StatusResponse {
+        self.ok(Method::GET, "/game/status", Some(player), None::<()>)
+            .await
+    }
+
+    async fn round_status(&self, player: &Player) -> RoundStatus {
+        match self.status(player).await {
+            StatusResponse::Round(round) => round,
+            other => panic!("expected round status, got {other:?}"),
+        }
+    }
+
+    async fn play(&self, player: &Player, card: Card) {
+        self.ok::<()>(Method::POST, &play_uri(card), Some(player), None::<()>)
+            .await;
+    }
+}
+
+fn join_data(id: GameId, name: &str, password: &str) -> JoinGameData {
+    JoinGameData {
+        id,
+        name: name.to_owned(),
+        password: password.to_owned(),
+    }
+}
+
+fn play_uri(card: Card) -> String {
+    let card = serde_json::to_value(card).unwrap();
+    format!(
+        "/game/play?suit={}&rank={}",
+        card["suit"].as_str().unwrap(),
+        card["rank"].as_str().unwrap()
+    )
+}
+
+/// Action chosen by the player whose turn it is.
+enum Move {
+    Play(Card),
+    Take,
+    Retreat,
+}
+
+/// Chooses the cheapest valid move.
+fn choose_move(round: &RoundStatus) -> Move {
+    let trump = round.trump.suit;
+    let cost = |card: &Card| (card.suit == trump, card.rank);
+    if round.turn == round.defender {
+        let (attacking, _) = round
+            .table
+            .iter()
+            .find(|(_, defending)| defending.is_none())
+            .unwrap();
+        round
+            .hand
+            .iter()
+            .filter(|card| card.can_beat(attacking, trump))
+            .min_by_key(cost)
+            .map_or(Move::Take, Move::Play)
+    } else {
+        if round.table.count() >= 6 {
+            return Move::Retreat;
+        }
+        let on_table: Vec<_> = round
+            .table
+            .iter()
+            .flat_map(|(attacking, defending)| [Some(attacking), defending])
+            .flatten()
+            .collect();
+        round
+            .hand
+            .iter()
+            .filter(|card| on_table.is_empty() || on_table.iter().any(|c| c.rank == card.rank))
+            .min_by_key(cost)
+            .map_or(Move::Retreat, Move::Play)
+    }
+}
+
+#[tokio::test]
+async fn test_full_game() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.players.len(), 2);
+
+    server.start(&host, &guest).await;
+    let players = [host, guest];
+
+    let mut moves = 0;
+    let finished = loop {
+        let round = match server.status(&players[0]).await {
+            StatusResponse::Round(round) => round,
+            StatusResponse::Finished(finished) => break finished,
+            StatusResponse::Lobby(_) => panic!("game shouldn't return to lobby"),
+        };
+        let player = players.iter().find(|p| p.id == round.turn).unwrap();
+        let round = server.round_status(player).await;
+        match choose_move(&round) {
+            Move::Play(card) => server.play(player, card).await,
+            Move::Take => {
+                server
+                    .ok::<()>(Method::POST, "/game/take", Some(player), None::<()>)
+                    .await
+            }
+            Move::Retreat => {
+                server
+                    .ok::<()>(Method::POST, "/game/retreat", Some(player), None::<()>)
+                    .await
+            }
+        }
+        moves += 1;
+        assert!(moves < 1000, "game should finish");
+    };
+
+    assert!(players.iter().any(|player| player.id == finished.winner));
+    let StatusResponse::Finished(other) = server.status(&players[1]).await else {
+        panic!("game should be finished for every player");
+    };
+    assert_eq!(other.winner, finished.winner);
+}
+
+#[tokio::test]
+async fn test_seeded_deck_deals_same_cards() {
+    let server = TestServer::new();
+    let [first, _] = server.start_game().await;
+    let [second, _] = server.start_game().await;
+
+    let first = server.round_status(&first).await;
+    let second = server.round_status(&second).await;
+    assert_eq!(first.trump, second.trump);
+    assert_eq!(
+        first.hand.iter().collect::<Vec<_>>(),
+        second.hand.iter().collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn test_wrong_moves_are_rejected() {
+    let server = TestServer::new();
+    let players = server.start_game().await;
+    let round = server.round_status(&players[0]).await;
+    let attacker = players.iter().find(|p| p.id == round.attacker).unwrap();
+    let defender = players.iter().find(|p| p.id == round.defender).unwrap();
+
+    let error = server
+        .player_err(Method::POST, "/game/take", attacker)
+        .await;
+    assert!(matches!(error, ApiError::Action(ActionError::InvalidTurn)));
+    let error = server
+        .player_err(Method::POST, "/game/retreat", attacker)
+        .await;
+    assert!(matches!(error, ApiError::Action(ActionError::CantRetreat)));
+    let error = server
+        .player_err(Method::POST, "/game/take", defender)
+        .await;
+    assert!(matches!(error, ApiError::Action(ActionError::CantTake)));
+
+    let defender_card = server.round_status(defender).await.hand.iter().next();
+    let error = server
+        .player_err(Method::POST, &play_uri(defender_card.unwrap()), attacker)
+        .await;
+    assert!(matches!(error, ApiError::PlayCard(_)));
+}
+
+#[tokio::test]
+async fn test_auth_failures() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let (status, error) = server
+        .err(Method::GET, "/game/status", None, None::<()>)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(matches!(
+        error,
+        ApiError::AuthFailed(AuthFailed::NoAuthHeader)
+    ));
+
+    let basic = format!("Basic {}", host.token.as_str());
+    let (status, error) = server
+        .err(Method::GET, "/game/status", Some(&basic), None::<()>)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(matches!(
+        error,
+        ApiError::AuthFailed(AuthFailed::InvalidAuthHeader)
+    ));
+
+    let tampered = format!("Bearer {}x", host.token.as_str());
+    let (status, error) = server
+        .err(Method::GET, "/game/status", Some(&tampered), None::<()>)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(matches!(
+        error,
+        ApiError::AuthFailed(AuthFailed::TokenDoesntMatch)
+    ));
+
+    // Tokens are signed with key of the server that issued them.
+    let other_server = TestServer::new();
+    let foreign = other_server.create("host").await;
+    let error = server
+        .player_err(Method::GET, "/game/status", &foreign)
+        .await;
+    assert!(matches!(
+        error,
+        ApiError::AuthFailed(AuthFailed::TokenDoesntMatch)
+    ));
+
+    let guest_token = format!("Bearer {}", guest.token.as_str());
+    let (status, error) = server
+        .err(Method::POST, "/game/start", Some(&guest_token), None::<()>)
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::NotHost)));
+}
+
+#[tokio::test]
+async fn test_join_failures() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+
+    let (status, error) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(join_data(host.game_id, "guest", "wrong")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(matches!(error, ApiError::InvalidPassword));
+
+    let missing = GameId::new(host.game_id.0.wrapping_add(1));
+    let (status, error) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(join_data(missing, "guest", PASSWORD)),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(matches!(error, ApiError::GameNotFound(_)));
+
+    server.join(host.game_id, "guest").await;
+    let (_, error) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(join_data(host.game_id, "third", PASSWORD)),
+        )
+        .await;
+    assert!(matches!(error, ApiError::TooManyPlayers));
+}
+
+#[tokio::test]
+async fn test_oversized_join_body() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+
+    let name = "x".repeat(8 * 1024);
+    let (status, error) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(join_data(host.game_id, &name, PASSWORD)),
+        )
+        .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(matches!(error, ApiError::PayloadTooLarge));
+}
+
+#[tokio::test]
+async fn test_phase_errors() {
+    let server = TestServer::new();
+
+    // Round endpoints are not available in lobby.
+    let host = server.create("host").await;
+    for uri in ["/game/take", "/game/retreat"] {
+        let error = server.player_err(Method::POST, uri, &host).await;
+        assert!(matches!(error, ApiError::InvalidPhase(_)), "{uri}");
+    }
+    let card = Card {
+        suit: CardSuit::Heart,
+        rank: CardRank::Six,
+    };
+    let error = server
+        .player_err(Method::POST, &play_uri(card), &host)
+        .await;
+    assert!(matches!(error, ApiError::InvalidPhase(_)));
+
+    let error = server.player_err(Method::POST, "/game/start", &host).await;
+    assert!(matches!(
+        error,
+        ApiError::Action(ActionError::NotEnoughPlayers)
+    ));
+
+    // Lobby endpoints are not available during round.
+    let [host, _] = server.start_game().await;
+    let (_, error) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(join_data(host.game_id, "late", PASSWORD)),
+        )
+        .await;
+    assert!(matches!(error, ApiError::InvalidPhase(_)));
+    let error = server.player_err(Method::POST, "/game/start", &host).await;
+    assert!(matches!(error, ApiError::InvalidPhase(_)));
+}
+
+#[tokio::test]
+async fn test_public_lobbies() {
+    let server = TestServer::new();
+    let public = server
+        .create_with(CreateGameData {
+            name: String::from("public"),
+            password: PASSWORD.to_owned(),
+            rules: Rules::default(),
+            public: true,
+        })
+        .await;
+    server.create("private").await;
+
+    let lobbies: Vec<LobbySummary> = server.ok(Method::GET, "/lobbies", None, None::<()>).await;
+    assert_eq!(lobbies.len(), 1);
+    assert_eq!(lobbies[0].id, public.game_id);
+    assert_eq!(lobbies[0].host, "public");
+    assert_eq!(lobbies[0].players, 1);
+    assert!(lobbies[0].has_password);
+
+    // Full lobbies can't be joined, so they are not listed.
+    server.join(public.game_id, "guest").await;
+    let lobbies: Vec<LobbySummary> = server.ok(Method::GET, "/lobbies", None, None::<()>).await;
+    assert!(lobbies.is_empty());
+
+    let (status, error) = server
+        .err(
+            Method::POST,
+            "/create",
+            None,
+            Some(CreateGameData {
+                name: String::from("host"),
+                password: String::new(),
+                rules: Rules {
+                    players: 7,
+                    ..Rules::default()
+                },
+                public: true,
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(matches!(error, ApiError::BadRequest(_)));
+}
+
+#[tokio::test]
+async fn test_matchmaking_queue() {
+    let server = TestServer::new();
+    let enter = |name: &str| {
+        server.ok::<QueueStatus>(
+            Method::POST,
+            "/queue",
+            None,
+            Some(QueueData {
+                name: name.to_owned(),
+                rules: Rules::default(),
+            }),
+        )
+    };
+
+    let QueueStatus::Waiting { ticket, players } = enter("first").await else {
+        panic!("first player shouldn't be matched");
+    };
+    assert_eq!(players, 1);
+    let QueueStatus::Matched(second) = enter("second").await else {
+        panic!("second player should complete the game");
+    };
+    let status_uri = format!("/queue/status?ticket={}", ticket.as_str());
+    let QueueStatus::Matched(first) = server.ok(Method::GET, &status_uri, None, None::<()>).await
+    else {
+        panic!("first player should be matched");
+    };
+
+    let players = [first, second].map(
+        |JoinGameResponse::Ok {
+             game_id,
+             player_id,
+             token,
+         }| Player {
+            game_id,
+            id: player_id,
+            token,
+        },
+    );
+    assert_eq!(players[0].game_id, players[1].game_id);
+    assert_ne!(players[0].id, players[1].id);
+    for player in &players {
+        server.round_status(player).await;
+    }
+
+    // Game is not joinable by players outside of the queue.
+    let (status, _) = server
+        .err(
+            Method::POST,
+            "/join",
+            None,
+            Some(JoinGameData {
+                id: players[0].game_id,
+                name: String::from("stranger"),
+                password: String::new(),
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let QueueStatus::Waiting { ticket, .. } = enter("third").await else {
+        panic!("third player shouldn't be matched");
+    };
+    let leave_uri = format!("/queue/leave?ticket={}", ticket.as_str());
+    server
+        .ok::<()>(Method::POST, &leave_uri, None, None::<()>)
+        .await;
+    let (status, error) = server.err(Method::POST, &leave_uri, None, None::<()>).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(matches!(error, ApiError::NotQueued));
+}
+
+#[tokio::test]
+async fn test_spectator() {
+    let server = TestServer::new();
+    let [host, guest] = server.start_game().await;
+
+    let data = SpectateData {
+        id: host.game_id,
+        password: PASSWORD.to_owned(),
+    };
+    let SpectateResponse::Ok { game_id, token } =
+        server.ok(Method::POST, "/spectate", None, Some(data)).await;
+    assert_eq!(game_id, host.game_id);
+    let spectator = Player {
+        game_id,
+        id: PlayerId::new(0),
+        token,
+    };
+
+    let status: SpectatorStatus = server
+        .ok(
+            Method::GET,
+            "/spectate/status",
+            Some(&spectator),
+            None::<()>,
+        )
+        .await;
+    let SpectatorStatus::Round(round) = status else {
+        panic!("expected round status, got {status:?}");
+    };
+    assert_eq!(round.players.len(), 2);
+    assert!(round.players.iter().all(|player| player.cards_number == 6));
+    assert!(round.discard.is_empty());
+    let json = serde_json::to_value(&round).unwrap();
+    assert!(json.get("hand").is_none());
+
+    let card = server.round_status(&host).await.hand.iter().next().unwrap();
+    let (status, error) = server
+        .err(
+            Method::POST,
+            &play_uri(card),
+            Some(&format!("Bearer {}", spectator.token.as_str())),
+            None::<()>,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::Spectator)));
+    let error = server
+        .player_err(Method::GET, "/game/status", &spectator)
+        .await;
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::Spectator)));
+
+    let data = SpectateData {
+        id: guest.game_id,
+        password: "wrong".to_owned(),
+    };
+    let (_, error) = server
+        .err(Method::POST, "/spectate", None, Some(data))
+        .await;
+    assert!(matches!(error, ApiError::InvalidPassword));
+}
+
+#[tokio::test]
+async fn test_chat() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let message: ChatMessage = server
+        .ok(
+            Method::POST,
+            "/game/chat",
+            Some(&host),
+            Some(PostMessageData {
+                text: String::from("  hello  "),
+            }),
+        )
+        .await;
+    assert_eq!(message.text, "hello");
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.chat, vec![message]);
+
+    let token = format!("Bearer {}", guest.token.as_str());
+    for text in [String::new(), "a".repeat(201)] {
+        let (status, error) = server
+            .err(
+                Method::POST,
+                "/game/chat",
+                Some(&token),
+                Some(PostMessageData { text }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(matches!(error, ApiError::BadRequest(_)));
+    }
+
+    server.start(&host, &guest).await;
+    let round = server.round_status(&guest).await;
+    assert_eq!(round.chat.len(), 1, "chat should survive start of the game");
+    for _ in 0..20 {
+        server
+            .ok::<ChatMessage>(
+                Method::POST,
+                "/game/chat",
+                Some(&guest),
+                Some(PostMessageData {
+                    text: String::from("spam"),
+                }),
+            )
+            .await;
+    }
+    let (status, error) = server
+        .err(
+            Method::POST,
+            "/game/chat",
+            Some(&token),
+            Some(PostMessageData {
+                text: String::from("spam"),
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(matches!(error, ApiError::RateLimited(_)));
+}
+
+#[tokio::test]
+async fn test_kick() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let error = server
+        .player_err(Method::POST, &kick_uri(guest.id), &guest)
+        .await;
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::NotHost)));
+    let error = server
+        .player_err(Method::POST, &kick_uri(host.id), &host)
+        .await;
+    assert!(matches!(error, ApiError::BadRequest(_)));
+
+    server
+        .ok::<()>(Method::POST, &kick_uri(guest.id), Some(&host), None::<()>)
+        .await;
+    let StatusResponse::Lobby(lobby) = server.status(&host).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.players.len(), 1);
+
+    let token = format!("Bearer {}", guest.token.as_str());
+    let (status, error) = server
+        .err(Method::GET, "/game/status", Some(&token), None::<()>)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::Kicked)));
+    let error = server
+        .player_err(Method::POST, &kick_uri(guest.id), &host)
+        .await;
+    assert!(matches!(error, ApiError::BadRequest(_)));
+}
+
+fn kick_uri(player: PlayerId) -> String {
+    format!("/game/kick?player={player}")
+}
+
+#[tokio::test]
+async fn test_host_leaves_lobby() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.host, Some(host.id));
+
+    server
+        .ok::<()>(Method::POST, "/game/leave", Some(&host), None::<()>)
+        .await;
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.host, Some(guest.id));
+    let error = server.player_err(Method::POST, "/game/ready", &host).await;
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::Kicked)));
+    let error = server.player_err(Method::POST, "/game/start", &guest).await;
+    assert!(matches!(
+        error,
+        ApiError::Action(ActionError::NotEnoughPlayers)
+    ));
+}
+
+#[tokio::test]
+async fn test_swap_seats() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let swap = "/game/seats/swap?first=0&second=1";
+    let error = server.player_err(Method::POST, swap, &guest).await;
+    assert!(matches!(error, ApiError::AuthFailed(AuthFailed::NotHost)));
+    let error = server
+        .player_err(Method::POST, "/game/seats/swap?first=0&second=2", &host)
+        .await;
+    assert!(matches!(error, ApiError::BadRequest(_)));
+
+    server
+        .ok::<()>(Method::POST, swap, Some(&host), None::<()>)
+        .await;
+    let StatusResponse::Lobby(lobby) = server.status(&host).await else {
+        panic!("game should be in lobby");
+    };
+    let seats: Vec<_> = lobby.players.iter().map(|p| (p.id, p.seat)).collect();
+    assert_eq!(seats, [(guest.id, 0), (host.id, 1)]);
+}
+
+#[tokio::test]
+async fn test_ready_check() {
+    let server = TestServer::new();
+    let host = server.create("host").await;
+    let guest = server.join(host.game_id, "guest").await;
+
+    let ready: bool = server
+        .ok(Method::POST, "/game/ready", Some(&guest), None::<()>)
+        .await;
+    assert!(ready);
+    let error = server.player_err(Method::POST, "/game/start", &host).await;
+    assert!(matches!(
+        error,
+        ApiError::Action(ActionError::PlayersNotReady)
+    ));
+
+    let ready: bool = server
+        .ok(Method::POST, "/game/ready", Some(&host), None::<()>)
+        .await;
+    assert!(ready);
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert!(lobby.is_ready(host.id) && lobby.is_ready(guest.id));
+    assert!(lobby.can_start());
+
+    let ready: bool = server
+        .ok(Method::POST, "/game/ready", Some(&guest), None::<()>)
+        .await;
+    assert!(!ready);
+    let StatusResponse::Lobby(lobby) = server.status(&guest).await else {
+        panic!("game should be in lobby");
+    };
+    assert_eq!(lobby.ready, [host.id]);
+    assert_eq!(lobby.countdown, None);
+}
+
+#[tokio::test]
+async fn test_leave_ongoing_game() {
+    let server = TestServer::new();
+    let [host, guest] = server.start_game().await;
+
+    server
+        .ok::<()>(Method::POST, "/game/leave", Some(&guest), None::<()>)
+        .await;
+    let StatusResponse::Finished(finished) = server.status(&host).await else {
+        panic!("game should be finished");
+    };
+    assert_eq!(finished.winner, host.id);
+    assert_eq!(finished.forfeited, Some(guest.id));
+    assert_eq!(finished.forfeited_name(), Some("guest"));
+
+    let error = server.player_err(Method::POST, "/game/leave", &host).await;
+    assert!(matches!(error, ApiError::InvalidPhase(_)));
+}
+
+#[tokio::test]
+async fn test_turn_time() {
+    let server = TestServer::new();
+    let create = |turn_time| CreateGameData {
+        name: String::from("host"),
+        password: PASSWORD.to_owned(),
+        rules: Rules {
+            turn_time,
+            ..Rules::default()
+        },
+        public: false,
+    };
+    let (status, error) = server
+        .err(Method::POST, "/create", None, Some(create(Some(0))))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(matches!(error, ApiError::BadRequest(_)));
+
+    let host = server.create_with(create(Some(30))).await;
+    let guest = server.join(host.game_id, "guest").await;
+    server.start(&host, &guest).await;
+    let round = server.round_status(&guest).await;
+    assert!(matches!(round.turn_time_left, Some(29..=30)));
+
+    let [host, _] = server.start_game().await;
+    let round = server.round_status(&host).await;
+    assert_eq!(round.turn_time_left, None);
+}